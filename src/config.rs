@@ -1,13 +1,19 @@
-use crate::game::GlobalStatus;
+use crate::display;
+use crate::game::{
+    self, file::Pips, GlobalStatus, Rule, DEFAULT_ALERT_SECONDS, DEFAULT_LOW_FUEL_FRACTION,
+    DEFAULT_TIMER_WARNING_SECONDS, X52PRO_DEVICE,
+};
 use crate::x52pro::{
-    device::{BooleanLightMode, LightMode, RedAmberGreenLightMode},
+    device::{BooleanLightMode, Input, LightMode, RedAmberGreenLightMode, MFD_LINE_COUNT},
     StatusLevelToModeMapper,
 };
 use log::info;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 /// Raw configuration string values (as read from a configuraiton file) for a specific game mode.
@@ -26,7 +32,109 @@ pub struct Config {
     files: Option<Files>,
     default: ModeConfig,
     hardpoints_deployed: Option<ModeConfig>,
+    hardpoints_deployed_analysis: Option<ModeConfig>,
     night_vision: Option<ModeConfig>,
+    on_foot: Option<ModeConfig>,
+    srv: Option<ModeConfig>,
+    fighter: Option<ModeConfig>,
+    multicrew: Option<ModeConfig>,
+    galaxy_map: Option<ModeConfig>,
+    system_map: Option<ModeConfig>,
+    fss: Option<ModeConfig>,
+    saa: Option<ModeConfig>,
+    station_services: Option<ModeConfig>,
+    comms_panel: Option<ModeConfig>,
+    #[serde(skip_serializing)]
+    rules: Option<Vec<Rule>>,
+    #[serde(skip_serializing)]
+    fuel_scoop: Option<FuelScoopConfig>,
+    #[serde(skip_serializing)]
+    pips: Option<PipsConfig>,
+    #[serde(skip_serializing)]
+    fire_groups: Option<BTreeMap<String, FireGroupConfig>>,
+    #[serde(skip_serializing)]
+    alert: Option<AlertConfig>,
+    #[serde(skip_serializing)]
+    mfd: Option<MfdConfig>,
+    #[serde(skip_serializing)]
+    timers: Option<TimersConfig>,
+    #[serde(skip_serializing)]
+    devices: Option<BTreeMap<String, DeviceConfig>>,
+}
+
+/// Configuration of the fuel scoop light, which has no game control to take
+/// the input from.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+struct FuelScoopConfig {
+    input: Option<Input>,
+    low_fuel: Option<f64>,
+}
+
+/// Configuration of the inputs whose lights show the pips for each system
+/// instead of control states.
+#[derive(Debug, Deserialize, PartialEq)]
+struct PipsConfig {
+    systems: Option<Input>,
+    engines: Option<Input>,
+    weapons: Option<Input>,
+}
+
+/// Raw configuration values for the fire buttons when a fire group is
+/// selected.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+struct FireGroupConfig {
+    fire: Option<(BooleanLightMode, RedAmberGreenLightMode)>,
+    fire_a: Option<(BooleanLightMode, RedAmberGreenLightMode)>,
+    fire_b: Option<(BooleanLightMode, RedAmberGreenLightMode)>,
+    fire_d: Option<(BooleanLightMode, RedAmberGreenLightMode)>,
+    fire_e: Option<(BooleanLightMode, RedAmberGreenLightMode)>,
+}
+
+/// Configuration of the high-priority danger alert.
+#[derive(Debug, Deserialize, PartialEq)]
+struct AlertConfig {
+    duration: Option<f64>,
+}
+
+/// Configuration of the light showing countdown timers started by journal
+/// events, which have no game control to take the input from.
+#[derive(Debug, Deserialize, PartialEq)]
+struct TimersConfig {
+    input: Option<Input>,
+    warning: Option<f64>,
+}
+
+/// Configuration of one of several joysticks, which are numbered from zero in
+/// the order they're found unless given a serial number.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+struct DeviceConfig {
+    serial: Option<String>,
+    bindings_device: Option<String>,
+}
+
+/// A joystick to drive, chosen by its serial number if given or else its
+/// number in the order they're found, with the name of its device in the
+/// bindings file.
+#[derive(Debug, PartialEq)]
+pub struct DeviceSettings {
+    pub index: usize,
+    pub serial: Option<String>,
+    pub bindings_device: String,
+}
+
+/// Configuration of the text shown on the MFD as pages of templates.
+#[derive(Debug, Deserialize, PartialEq)]
+struct MfdConfig {
+    pages: Vec<MfdPageConfig>,
+}
+
+/// Configuration of a page of the MFD as a template for each line.
+#[derive(Debug, Deserialize, PartialEq)]
+struct MfdPageConfig {
+    lines: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
@@ -34,8 +142,7 @@ struct Files {
     bindings: Option<String>,
 }
 
-const DEFAULT_BINDINGS_FILE_PATH: &str =
-    r"Frontier Developments\Elite Dangerous\Options\Bindings\Custom.4.0.binds";
+const DEFAULT_BINDINGS_FILE_NAME: &str = "Custom.4.0.binds";
 
 impl Config {
     /// Returns a new instance constructed by loading the give configuration
@@ -45,10 +152,84 @@ impl Config {
         Self::from_toml(&toml)
     }
 
-    /// Returns a new instance constructed from the referenced TOML `String`.
-    /// Panics if the TOML cannot be parsed.
-    fn from_toml(toml: &String) -> Self {
-        toml::from_str(&toml).expect("Could not load configuration")
+    /// Returns a new instance constructed from the referenced TOML string.
+    /// Panics if the TOML cannot be parsed or a value is out of range.
+    fn from_toml(toml: &str) -> Self {
+        let config: Self = toml::from_str(toml).expect("Could not load configuration");
+
+        let low_fuel_fraction = config.low_fuel_fraction();
+        if !(0.0..=1.0).contains(&low_fuel_fraction) {
+            panic!(
+                "Low fuel must be a fraction between 0 and 1 but is {}",
+                low_fuel_fraction
+            );
+        }
+
+        let alert_seconds = config.alert_seconds();
+        if !(alert_seconds >= 0.0 && alert_seconds.is_finite()) {
+            panic!(
+                "Alert duration must be a number of seconds but is {}",
+                alert_seconds
+            );
+        }
+
+        let timer_warning_seconds = config.timer_warning_seconds();
+        if !(timer_warning_seconds >= 0.0 && timer_warning_seconds.is_finite()) {
+            panic!(
+                "Timer warning must be a number of seconds but is {}",
+                timer_warning_seconds
+            );
+        }
+
+        let mfd_pages = config.mfd_pages();
+        if mfd_pages.is_empty() {
+            panic!("MFD must have at least one page");
+        }
+        for templates in &mfd_pages {
+            if templates.len() > MFD_LINE_COUNT {
+                panic!(
+                    "MFD has {} lines but {} are configured",
+                    MFD_LINE_COUNT,
+                    templates.len()
+                );
+            }
+            for template in templates {
+                if let Some(name) = display::unknown_names(template).first() {
+                    panic!("Unknown value {} in MFD line {}", name, template);
+                }
+            }
+        }
+
+        // Table keys are always strings so check they're device numbers.
+        if let Some(devices) = &config.devices {
+            if devices.is_empty() {
+                panic!("Devices must include at least one device");
+            }
+            for key in devices.keys() {
+                if key.parse::<usize>().is_err() {
+                    panic!("Device must be a number but is {}", key);
+                }
+            }
+            let mut serials: Vec<&String> = devices
+                .values()
+                .filter_map(|device| device.serial.as_ref())
+                .collect();
+            serials.sort();
+            if let Some(serial) = serials.windows(2).find(|pair| pair[0] == pair[1]) {
+                panic!("Device serial {} is configured more than once", serial[0]);
+            }
+        }
+
+        // Table keys are always strings so check they're fire group numbers.
+        if let Some(fire_groups) = &config.fire_groups {
+            for key in fire_groups.keys() {
+                if key.parse::<u8>().is_err() {
+                    panic!("Fire group must be a number but is {}", key);
+                }
+            }
+        }
+
+        config
     }
 
     /// Returns a `StatusLevelToModeMapper` for the given `GlobalStatus` value,
@@ -62,7 +243,20 @@ impl Config {
             GlobalStatus::HardpointsDeployed => {
                 self.mode_config_or_default(&self.hardpoints_deployed)
             }
+            GlobalStatus::HardpointsDeployedAnalysis => {
+                self.mode_config_or_default(&self.hardpoints_deployed_analysis)
+            }
             GlobalStatus::NightVisionOn => self.mode_config_or_default(&self.night_vision),
+            GlobalStatus::OnFoot => self.mode_config_or_default(&self.on_foot),
+            GlobalStatus::InSrv => self.mode_config_or_default(&self.srv),
+            GlobalStatus::InFighter => self.mode_config_or_default(&self.fighter),
+            GlobalStatus::Multicrew => self.mode_config_or_default(&self.multicrew),
+            GlobalStatus::GalaxyMap => self.mode_config_or_default(&self.galaxy_map),
+            GlobalStatus::SystemMap => self.mode_config_or_default(&self.system_map),
+            GlobalStatus::FullSpectrumScanner => self.mode_config_or_default(&self.fss),
+            GlobalStatus::SurfaceAreaAnalysis => self.mode_config_or_default(&self.saa),
+            GlobalStatus::StationServices => self.mode_config_or_default(&self.station_services),
+            GlobalStatus::CommsPanel => self.mode_config_or_default(&self.comms_panel),
         };
 
         StatusLevelToModeMapper::new(
@@ -83,6 +277,153 @@ impl Config {
         }
     }
 
+    /// Returns the configured status rules, which are empty if there are none
+    /// and the built-in rules apply.
+    pub fn rules(&self) -> &[Rule] {
+        match &self.rules {
+            Some(rules) => rules,
+            None => &[],
+        }
+    }
+
+    /// Returns the fraction of the main tank below which fuel is low.
+    pub fn low_fuel_fraction(&self) -> f64 {
+        self.fuel_scoop
+            .as_ref()
+            .and_then(|fuel_scoop| fuel_scoop.low_fuel)
+            .unwrap_or(DEFAULT_LOW_FUEL_FRACTION)
+    }
+
+    /// Returns the templates for the lines of text shown on each page of the
+    /// MFD.
+    pub fn mfd_pages(&self) -> Vec<Vec<String>> {
+        match &self.mfd {
+            Some(mfd) => mfd.pages.iter().map(|page| page.lines.clone()).collect(),
+            None => display::DEFAULT_PAGES
+                .iter()
+                .map(|templates| {
+                    templates
+                        .iter()
+                        .map(|template| template.to_string())
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns how long a danger alert lasts.
+    pub fn alert_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.alert_seconds())
+    }
+
+    fn alert_seconds(&self) -> f64 {
+        self.alert
+            .as_ref()
+            .and_then(|alert| alert.duration)
+            .unwrap_or(DEFAULT_ALERT_SECONDS)
+    }
+
+    /// Returns how long before a countdown timer expires that it's about to
+    /// expire.
+    pub fn timer_warning(&self) -> Duration {
+        Duration::from_secs_f64(self.timer_warning_seconds())
+    }
+
+    fn timer_warning_seconds(&self) -> f64 {
+        self.timers
+            .as_ref()
+            .and_then(|timers| timers.warning)
+            .unwrap_or(DEFAULT_TIMER_WARNING_SECONDS)
+    }
+
+    /// Returns the settings of each joystick to drive in order of their
+    /// numbers. Only the first joystick is driven if none are configured.
+    pub fn devices(&self) -> Vec<DeviceSettings> {
+        let mut devices: Vec<DeviceSettings> = match &self.devices {
+            Some(devices) => devices
+                .iter()
+                .map(|(key, device)| DeviceSettings {
+                    index: key.parse().expect("Device must be a number"),
+                    serial: device.serial.clone(),
+                    bindings_device: device
+                        .bindings_device
+                        .clone()
+                        .unwrap_or_else(|| String::from(X52PRO_DEVICE)),
+                })
+                .collect(),
+            None => vec![DeviceSettings {
+                index: 0,
+                serial: None,
+                bindings_device: String::from(X52PRO_DEVICE),
+            }],
+        };
+
+        devices.sort_by_key(|device| device.index);
+        devices
+    }
+
+    /// Returns the input configured to show the countdown timers, if any.
+    pub fn timers_input(&self) -> Option<Input> {
+        self.timers.as_ref().and_then(|timers| timers.input)
+    }
+
+    /// Returns the input configured to show the fuel scoop status, if any.
+    pub fn fuel_scoop_input(&self) -> Option<Input> {
+        self.fuel_scoop
+            .as_ref()
+            .and_then(|fuel_scoop| fuel_scoop.input)
+    }
+
+    /// Returns the light mode for each input configured to show pips given
+    /// the current pips: off for none, then green, amber and red as the pips
+    /// increase.
+    pub fn pips_input_light_modes(&self, pips: Pips) -> Vec<(Input, LightMode)> {
+        let mut input_light_modes = Vec::new();
+
+        if let Some(pips_config) = &self.pips {
+            for (input, half_pips) in [
+                (pips_config.systems, pips.systems),
+                (pips_config.engines, pips.engines),
+                (pips_config.weapons, pips.weapons),
+            ] {
+                if let Some(input) = input {
+                    input_light_modes.push((input, light_mode_for_half_pips(half_pips)));
+                }
+            }
+        }
+
+        input_light_modes
+    }
+
+    /// Returns the configured light mode for each fire button when the given
+    /// fire group (numbered from zero) is selected.
+    pub fn fire_group_input_light_modes(&self, fire_group: u8) -> Vec<(Input, LightMode)> {
+        let mut input_light_modes = Vec::new();
+
+        let fire_group_config = self.fire_groups.as_ref().and_then(|fire_groups| {
+            fire_groups
+                .iter()
+                .find(|(key, _)| key.parse() == Ok(fire_group))
+                .map(|(_, fire_group_config)| fire_group_config)
+        });
+
+        if let Some(fire_group_config) = fire_group_config {
+            for (input, value) in [
+                (Input::Fire, fire_group_config.fire),
+                (Input::FireA, fire_group_config.fire_a),
+                (Input::FireB, fire_group_config.fire_b),
+                (Input::FireD, fire_group_config.fire_d),
+                (Input::FireE, fire_group_config.fire_e),
+            ] {
+                if let Some(value) = value {
+                    input_light_modes.push((input, light_mode_from_config_values(value)));
+                }
+            }
+        }
+
+        input_light_modes
+    }
+
     /// Returns the configured path for the bindings file or the default if not
     /// configured.
     pub fn bindings_file_path(&self) -> PathBuf {
@@ -92,9 +433,7 @@ impl Config {
             }
         }
 
-        dirs::data_local_dir()
-            .expect("Can't find user app data directory")
-            .join(DEFAULT_BINDINGS_FILE_PATH)
+        game::file::bindings_dir_path().join(DEFAULT_BINDINGS_FILE_NAME)
     }
 }
 
@@ -104,6 +443,16 @@ fn light_mode_from_config_values(value: (BooleanLightMode, RedAmberGreenLightMod
     LightMode::new(boolean, red_amber_green)
 }
 
+/// Returns the `LightMode` showing the given number of half pips.
+fn light_mode_for_half_pips(half_pips: u8) -> LightMode {
+    match half_pips {
+        0 => LightMode::new(BooleanLightMode::Off, RedAmberGreenLightMode::Off),
+        1..=3 => LightMode::new(BooleanLightMode::On, RedAmberGreenLightMode::Green),
+        4..=5 => LightMode::new(BooleanLightMode::On, RedAmberGreenLightMode::Amber),
+        _ => LightMode::new(BooleanLightMode::On, RedAmberGreenLightMode::Red),
+    }
+}
+
 /// Writes a default configuration file to the given filename if that file does
 /// not exist. Panics if the file cannot be written, e.g. if the user does not
 /// have permission.
@@ -128,12 +477,31 @@ pub fn write_default_file_if_missing(config_filename: &str) {
             blocked: (BooleanLightMode::Off, RedAmberGreenLightMode::Off),
             alert: (BooleanLightMode::Flash, RedAmberGreenLightMode::AmberFlash),
         }),
+        hardpoints_deployed_analysis: None,
         night_vision: Some(ModeConfig {
             inactive: (BooleanLightMode::Off, RedAmberGreenLightMode::Off),
             active: (BooleanLightMode::On, RedAmberGreenLightMode::Green),
             blocked: (BooleanLightMode::Off, RedAmberGreenLightMode::Off),
             alert: (BooleanLightMode::Flash, RedAmberGreenLightMode::GreenFlash),
         }),
+        on_foot: None,
+        srv: None,
+        fighter: None,
+        multicrew: None,
+        galaxy_map: None,
+        system_map: None,
+        fss: None,
+        saa: None,
+        station_services: None,
+        comms_panel: None,
+        rules: None,
+        fuel_scoop: None,
+        pips: None,
+        fire_groups: None,
+        alert: None,
+        mfd: None,
+        timers: None,
+        devices: None,
     };
 
     let toml = toml::to_string(&config).expect("Could not serialize default configuration");
@@ -144,6 +512,19 @@ pub fn write_default_file_if_missing(config_filename: &str) {
 mod tests {
     use super::*;
 
+    const DEFAULT_TOML: &str = r#"
+        [default]
+        inactive = ["off", "green"]
+        active = ["on", "amber"]
+        blocked = ["on", "red"]
+        alert = ["flash", "red-amber"]"#;
+
+    /// Returns the config read from the default light modes followed by the
+    /// given sections.
+    fn config_from_sections(sections: &str) -> Config {
+        Config::from_toml(&format!("{}\n{}", DEFAULT_TOML, sections))
+    }
+
     #[test]
     fn config_from_toml_returns_an_instance() {
         let toml = r#"
@@ -163,7 +544,12 @@ mod tests {
             inactive = ["flash", "green"]
             active = ["flash", "amber"]
             blocked = ["off", "red"]
-            alert = ["on", "red-amber"]"#;
+            alert = ["on", "red-amber"]
+            [on-foot]
+            inactive = ["off", "off"]
+            active = ["on", "green"]
+            blocked = ["off", "red"]
+            alert = ["flash", "green-flash"]"#;
 
         let expected = Config {
             files: Some(Files {
@@ -181,12 +567,36 @@ mod tests {
                 blocked: (BooleanLightMode::Flash, RedAmberGreenLightMode::Red),
                 alert: (BooleanLightMode::Off, RedAmberGreenLightMode::RedAmber),
             }),
+            hardpoints_deployed_analysis: None,
             night_vision: Some(ModeConfig {
                 inactive: (BooleanLightMode::Flash, RedAmberGreenLightMode::Green),
                 active: (BooleanLightMode::Flash, RedAmberGreenLightMode::Amber),
                 blocked: (BooleanLightMode::Off, RedAmberGreenLightMode::Red),
                 alert: (BooleanLightMode::On, RedAmberGreenLightMode::RedAmber),
             }),
+            on_foot: Some(ModeConfig {
+                inactive: (BooleanLightMode::Off, RedAmberGreenLightMode::Off),
+                active: (BooleanLightMode::On, RedAmberGreenLightMode::Green),
+                blocked: (BooleanLightMode::Off, RedAmberGreenLightMode::Red),
+                alert: (BooleanLightMode::Flash, RedAmberGreenLightMode::GreenFlash),
+            }),
+            srv: None,
+            fighter: None,
+            multicrew: None,
+            galaxy_map: None,
+            system_map: None,
+            fss: None,
+            saa: None,
+            station_services: None,
+            comms_panel: None,
+            rules: None,
+            fuel_scoop: None,
+            pips: None,
+            fire_groups: None,
+            alert: None,
+            mfd: None,
+            timers: None,
+            devices: None,
         };
 
         assert_eq!(Config::from_toml(&String::from(toml)), expected);
@@ -210,12 +620,293 @@ mod tests {
                 alert: (BooleanLightMode::Flash, RedAmberGreenLightMode::RedAmber),
             },
             hardpoints_deployed: None,
+            hardpoints_deployed_analysis: None,
             night_vision: None,
+            on_foot: None,
+            srv: None,
+            fighter: None,
+            multicrew: None,
+            galaxy_map: None,
+            system_map: None,
+            fss: None,
+            saa: None,
+            station_services: None,
+            comms_panel: None,
+            rules: None,
+            fuel_scoop: None,
+            pips: None,
+            fire_groups: None,
+            alert: None,
+            mfd: None,
+            timers: None,
+            devices: None,
         };
 
         assert_eq!(Config::from_toml(&String::from(toml)), expected);
     }
 
+    #[test]
+    fn config_from_toml_reads_rules() {
+        let toml = r#"
+            [[rules]]
+            attribute = "boost"
+            any = ["cargo-scoop-deployed", "mass-locked"]
+            level = "blocked"
+
+            [[rules]]
+            attribute = "landing-gear"
+            all = ["landing-gear-deployed"]
+            level = "active""#;
+
+        assert_eq!(config_from_sections(toml).rules().len(), 2);
+    }
+
+    #[test]
+    fn config_from_toml_reads_fuel_scoop() {
+        let toml = r#"
+            [fuel-scoop]
+            input = "pov2-up"
+            low-fuel = 0.4"#;
+
+        let config = config_from_sections(toml);
+        assert_eq!(config.fuel_scoop_input(), Some(Input::PoV2Up));
+        assert_eq!(config.low_fuel_fraction(), 0.4);
+    }
+
+    #[test]
+    fn config_pips_input_light_modes_maps_pips_to_configured_inputs() {
+        let toml = r#"
+            [pips]
+            systems = "t1"
+            weapons = "t5""#;
+
+        let pips = Pips {
+            systems: 0,
+            engines: 8,
+            weapons: 5,
+        };
+
+        assert_eq!(
+            config_from_sections(toml).pips_input_light_modes(pips),
+            vec![
+                (
+                    Input::T1,
+                    LightMode::new(BooleanLightMode::Off, RedAmberGreenLightMode::Off)
+                ),
+                (
+                    Input::T5,
+                    LightMode::new(BooleanLightMode::On, RedAmberGreenLightMode::Amber)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn config_fire_group_input_light_modes_maps_selected_group() {
+        let toml = r#"
+            [fire-groups.0]
+            fire = ["on", "off"]
+            fire-a = ["on", "green"]
+
+            [fire-groups.1]
+            fire-a = ["on", "red"]"#;
+
+        let config = config_from_sections(toml);
+
+        assert_eq!(
+            config.fire_group_input_light_modes(1),
+            vec![(
+                Input::FireA,
+                LightMode::new(BooleanLightMode::On, RedAmberGreenLightMode::Red)
+            )]
+        );
+        assert_eq!(config.fire_group_input_light_modes(0).len(), 2);
+        assert_eq!(config.fire_group_input_light_modes(2), vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fire group must be a number but is first")]
+    fn config_from_toml_panics_for_fire_group_not_a_number() {
+        let toml = r#"
+            [fire-groups.first]
+            fire-a = ["on", "red"]"#;
+
+        config_from_sections(toml);
+    }
+
+    #[test]
+    fn config_alert_duration_defaults_and_reads_seconds() {
+        assert_eq!(
+            config_from_sections("").alert_duration(),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            config_from_sections("[alert]\nduration = 2.5").alert_duration(),
+            Duration::from_millis(2500)
+        );
+    }
+
+    #[test]
+    fn config_devices_defaults_and_reads_devices_in_order() {
+        assert_eq!(
+            config_from_sections("").devices(),
+            vec![DeviceSettings {
+                index: 0,
+                serial: None,
+                bindings_device: String::from("SaitekX52Pro")
+            }]
+        );
+
+        let toml = r#"
+            [devices.10]
+            serial = "A1"
+            bindings-device = "06A30762"
+
+            [devices.2]"#;
+        assert_eq!(
+            config_from_sections(toml).devices(),
+            vec![
+                DeviceSettings {
+                    index: 2,
+                    serial: None,
+                    bindings_device: String::from("SaitekX52Pro")
+                },
+                DeviceSettings {
+                    index: 10,
+                    serial: Some(String::from("A1")),
+                    bindings_device: String::from("06A30762")
+                }
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Device must be a number but is first")]
+    fn config_from_toml_panics_for_non_numeric_device() {
+        let toml = r#"
+            [devices.first]
+            bindings-device = "SaitekX52Pro""#;
+
+        config_from_sections(toml);
+    }
+
+    #[test]
+    #[should_panic(expected = "Device serial A1 is configured more than once")]
+    fn config_from_toml_panics_for_duplicate_device_serial() {
+        let toml = r#"
+            [devices.0]
+            serial = "A1"
+
+            [devices.1]
+            serial = "A1""#;
+
+        config_from_sections(toml);
+    }
+
+    #[test]
+    fn config_timers_reads_input_and_warning() {
+        let config = config_from_sections("");
+        assert_eq!(config.timers_input(), None);
+        assert_eq!(config.timer_warning(), Duration::from_secs(60));
+
+        let toml = r#"
+            [timers]
+            input = "t4"
+            warning = 30"#;
+        let config = config_from_sections(toml);
+        assert_eq!(config.timers_input(), Some(Input::T4));
+        assert_eq!(config.timer_warning(), Duration::from_secs(30));
+    }
+
+    #[test]
+    #[should_panic(expected = "Timer warning must be a number of seconds but is -1")]
+    fn config_from_toml_panics_for_negative_timer_warning() {
+        let toml = r#"
+            [timers]
+            warning = -1.0"#;
+
+        config_from_sections(toml);
+    }
+
+    #[test]
+    #[should_panic(expected = "Alert duration must be a number of seconds but is -1")]
+    fn config_from_toml_panics_for_negative_alert_duration() {
+        let toml = r#"
+            [alert]
+            duration = -1.0"#;
+
+        config_from_sections(toml);
+    }
+
+    #[test]
+    fn config_mfd_pages_defaults_and_reads_pages() {
+        let default_pages = config_from_sections("").mfd_pages();
+        assert_eq!(default_pages.len(), 4);
+        assert_eq!(
+            default_pages[0],
+            vec!["{system}", "{target}", "Fuel {fuel}%"]
+        );
+
+        let toml = r#"
+            [[mfd.pages]]
+            lines = ["{system}"]
+
+            [[mfd.pages]]
+            lines = ["Cargo {cargo}t"]"#;
+        assert_eq!(
+            config_from_sections(toml).mfd_pages(),
+            vec![vec!["{system}"], vec!["Cargo {cargo}t"]]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown value fule in MFD line Fuel {fule}%")]
+    fn config_from_toml_panics_for_unknown_mfd_value() {
+        let toml = r#"
+            [[mfd.pages]]
+            lines = ["Fuel {fule}%"]"#;
+
+        config_from_sections(toml);
+    }
+
+    #[test]
+    fn light_mode_for_half_pips_uses_green_amber_and_red() {
+        assert_eq!(
+            light_mode_for_half_pips(2).red_amber_green,
+            RedAmberGreenLightMode::Green
+        );
+        assert_eq!(
+            light_mode_for_half_pips(4).red_amber_green,
+            RedAmberGreenLightMode::Amber
+        );
+        assert_eq!(
+            light_mode_for_half_pips(8).red_amber_green,
+            RedAmberGreenLightMode::Red
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Low fuel must be a fraction between 0 and 1 but is 25")]
+    fn config_from_toml_panics_for_low_fuel_out_of_range() {
+        let toml = r#"
+            [fuel-scoop]
+            low-fuel = 25.0"#;
+
+        config_from_sections(toml);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown status `warp-drive`")]
+    fn config_from_toml_panics_for_invalid_rules() {
+        let toml = r#"
+            [[rules]]
+            attribute = "boost"
+            any = ["warp-drive"]
+            level = "blocked""#;
+
+        config_from_sections(toml);
+    }
+
     #[test]
     fn config_status_level_to_mode_mapper_returns_configured_mapped() {
         let default_light_config = (BooleanLightMode::On, RedAmberGreenLightMode::Green);
@@ -240,7 +931,26 @@ mod tests {
                 blocked: other_light_config,
                 alert: other_light_config,
             }),
+            hardpoints_deployed_analysis: None,
             night_vision: None,
+            on_foot: None,
+            srv: None,
+            fighter: None,
+            multicrew: None,
+            galaxy_map: None,
+            system_map: None,
+            fss: None,
+            saa: None,
+            station_services: None,
+            comms_panel: None,
+            rules: None,
+            fuel_scoop: None,
+            pips: None,
+            fire_groups: None,
+            alert: None,
+            mfd: None,
+            timers: None,
+            devices: None,
         };
 
         let actual_mapper = config.status_level_to_mode_mapper(GlobalStatus::Normal);
@@ -268,12 +978,31 @@ mod tests {
                 alert: default_light_config,
             },
             hardpoints_deployed: None,
+            hardpoints_deployed_analysis: None,
             night_vision: Some(ModeConfig {
                 inactive: night_vision_light_config,
                 active: night_vision_light_config,
                 blocked: night_vision_light_config,
                 alert: night_vision_light_config,
             }),
+            on_foot: None,
+            srv: None,
+            fighter: None,
+            multicrew: None,
+            galaxy_map: None,
+            system_map: None,
+            fss: None,
+            saa: None,
+            station_services: None,
+            comms_panel: None,
+            rules: None,
+            fuel_scoop: None,
+            pips: None,
+            fire_groups: None,
+            alert: None,
+            mfd: None,
+            timers: None,
+            devices: None,
         };
 
         let actual_mapper = config.status_level_to_mode_mapper(GlobalStatus::NightVisionOn);
@@ -312,7 +1041,26 @@ mod tests {
                 alert: default_light_config,
             },
             hardpoints_deployed: None,
+            hardpoints_deployed_analysis: None,
             night_vision: None,
+            on_foot: None,
+            srv: None,
+            fighter: None,
+            multicrew: None,
+            galaxy_map: None,
+            system_map: None,
+            fss: None,
+            saa: None,
+            station_services: None,
+            comms_panel: None,
+            rules: None,
+            fuel_scoop: None,
+            pips: None,
+            fire_groups: None,
+            alert: None,
+            mfd: None,
+            timers: None,
+            devices: None,
         };
 
         let expected_mapper = StatusLevelToModeMapper {
@@ -325,7 +1073,18 @@ mod tests {
         let global_statuses = vec![
             GlobalStatus::Normal,
             GlobalStatus::HardpointsDeployed,
+            GlobalStatus::HardpointsDeployedAnalysis,
             GlobalStatus::NightVisionOn,
+            GlobalStatus::OnFoot,
+            GlobalStatus::InSrv,
+            GlobalStatus::InFighter,
+            GlobalStatus::Multicrew,
+            GlobalStatus::GalaxyMap,
+            GlobalStatus::SystemMap,
+            GlobalStatus::FullSpectrumScanner,
+            GlobalStatus::SurfaceAreaAnalysis,
+            GlobalStatus::StationServices,
+            GlobalStatus::CommsPanel,
         ];
 
         for global_status in global_statuses {
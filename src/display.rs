@@ -0,0 +1,172 @@
+use crate::game::Ship;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The pages of lines shown on the MFD if not configured: navigation, cargo,
+/// combat and timers.
+pub const DEFAULT_PAGES: [&[&str]; 4] = [
+    &["{system}", "{target}", "Fuel {fuel}%"],
+    &["Cargo {cargo}t", "Fuel {fuel-main}t"],
+    &[
+        "Fire group {fire-group}",
+        "Pips {systems-pips}/{engines-pips}/{weapons-pips}",
+    ],
+    &[
+        "UTC {clock}",
+        "Dock {docking-timer}",
+        "Carrier {carrier-jump-timer}",
+    ],
+];
+
+// The name of the current time of day, which is shown in addition to the text
+// from the ship.
+const CLOCK_NAME: &str = "clock";
+
+// Shown in place of a value that isn't currently known.
+const UNKNOWN_VALUE: &str = "-";
+
+/// Renders pages of lines of text for the MFD from templates such as
+/// `Fuel {fuel}%`, where each name in braces is replaced with the current value
+/// from the ship.
+pub struct Display {
+    pages: Vec<Vec<String>>,
+}
+
+impl Display {
+    /// Returns a new instance that renders the given pages of templates, one
+    /// template per line.
+    pub fn new(pages: Vec<Vec<String>>) -> Self {
+        Self { pages }
+    }
+
+    /// Returns the lines of the given page rendered for the current state of
+    /// the ship. While the ship has a message from a recent journal event it
+    /// replaces the last line.
+    pub fn lines(&self, page: u32, ship: &Ship) -> Vec<String> {
+        let templates = match self.pages.get(page as usize) {
+            Some(templates) => templates,
+            None => return vec![],
+        };
+
+        let mut lines: Vec<String> = templates
+            .iter()
+            .map(|template| render(template, ship))
+            .collect();
+
+        if let (Some(message), Some(last_line)) = (ship.message(), lines.last_mut()) {
+            *last_line = message.to_string();
+        }
+
+        lines
+    }
+}
+
+/// Returns the names in the given template that the ship has no value for,
+/// e.g. misspellings.
+pub fn unknown_names(template: &str) -> Vec<&str> {
+    names(template)
+        .into_iter()
+        .filter(|name| *name != CLOCK_NAME && !Ship::is_text_name(name))
+        .collect()
+}
+
+/// Returns the names in braces in the given template. An unclosed brace is
+/// left as text.
+fn names(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        match rest[start..].find('}') {
+            Some(length) => {
+                names.push(&rest[start + 1..start + length]);
+                rest = &rest[start + length + 1..];
+            }
+            None => break,
+        }
+    }
+
+    names
+}
+
+/// Returns the hour and minute of the given time in UTC, which is the time
+/// used in the game.
+pub fn time_of_day(time: SystemTime) -> (u32, u32) {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or(0);
+
+    ((seconds / 3600 % 24) as u32, (seconds / 60 % 60) as u32)
+}
+
+/// Returns the template with each name replaced by its current value.
+fn render(template: &str, ship: &Ship) -> String {
+    let mut line = template.to_string();
+
+    for name in names(template) {
+        let value = if name == CLOCK_NAME {
+            let (hour, minute) = time_of_day(SystemTime::now());
+            Some(format!("{:02}:{:02}", hour, minute))
+        } else {
+            ship.text(name)
+        }
+        .unwrap_or_else(|| UNKNOWN_VALUE.to_string());
+        line = line.replacen(&format!("{{{}}}", name), &value, 1);
+    }
+
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn names_returns_each_name_in_braces() {
+        assert_eq!(names("{system} at {fuel}%"), vec!["system", "fuel"]);
+        assert_eq!(names("Fuel {fuel"), Vec::<&str>::new());
+        assert_eq!(names("No names"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn unknown_names_returns_names_without_values() {
+        assert_eq!(
+            unknown_names("{system} {fuel} {clock} {fule}"),
+            vec!["fule"]
+        );
+    }
+
+    #[test]
+    fn time_of_day_returns_utc_hour_and_minute() {
+        assert_eq!(time_of_day(UNIX_EPOCH), (0, 0));
+        assert_eq!(
+            time_of_day(UNIX_EPOCH + Duration::from_secs(1_621_037_710)),
+            (0, 15)
+        );
+        assert_eq!(
+            time_of_day(UNIX_EPOCH + Duration::from_secs(86_399)),
+            (23, 59)
+        );
+    }
+
+    #[test]
+    fn lines_replaces_unknown_values_with_placeholder() {
+        let display = Display::new(vec![vec![
+            String::from("Fuel {fuel}%"),
+            String::from("Text"),
+        ]]);
+
+        assert_eq!(display.lines(0, &Ship::new()), vec!["Fuel -%", "Text"]);
+        assert_eq!(display.lines(1, &Ship::new()), Vec::<String>::new());
+    }
+
+    #[test]
+    fn default_pages_only_use_known_names() {
+        for templates in DEFAULT_PAGES {
+            for template in templates {
+                assert_eq!(unknown_names(template), Vec::<&str>::new());
+            }
+        }
+    }
+}
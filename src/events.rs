@@ -1,4 +1,5 @@
 use crate::game::file::Status;
+use crate::x52pro::SoftButton;
 use std::path::PathBuf;
 
 #[derive(Debug, PartialEq)]
@@ -6,5 +7,8 @@ pub enum Event {
     NewJournalFile(PathBuf),
     AnimationTick,
     StatusUpdate(Status),
+    MfdPageActivated(usize, u32),
+    MfdSoftButton(usize, SoftButton),
+    DeviceConnected,
     Exit,
 }
@@ -2,7 +2,8 @@ use crate::game::file::{ControlBindings, Input as BindingsInput};
 use crate::x52pro::device::Input;
 use std::path::PathBuf;
 
-const X52PRO_DEVICE: &str = "SaitekX52Pro";
+/// The name of the X52Pro device in the bindings file, unless there are several.
+pub const X52PRO_DEVICE: &str = "SaitekX52Pro";
 const X52PRO_CLUTCH: &str = "Joy_31";
 const X52PRO_FIRE: &str = "Joy_2";
 const X52PRO_FIRE_A: &str = "Joy_3";
@@ -25,34 +26,109 @@ const X52PRO_Z_AXIS: &str = "Joy_ZAxis";
 pub enum Control {
     Boost,
     CargoScoop,
+    CycleFireGroupNext,
+    CycleFireGroupPrevious,
+    CycleNextPanel,
+    CyclePreviousPanel,
     ExternalLights,
     Hardpoints,
     HeatSink,
+    HumanoidFlashlight,
+    HumanoidHealthPack,
+    HumanoidNightVision,
+    HumanoidPrimaryFire,
+    HumanoidRechargeTool,
     Hyperspace,
     HyperSuperCombination,
+    IncreaseEnginesPower,
+    IncreaseSystemsPower,
+    IncreaseWeaponsPower,
     LandingGear,
     NightVision,
+    PlayerHudModeToggle,
+    ResetPowerDistribution,
     SilentRunning,
+    SrvDriveAssist,
+    SrvHandbrake,
+    SrvHeadlights,
+    SrvRecallDismissShip,
+    SrvTurret,
     Supercruise,
     Throttle,
+    UiBack,
+    UiDown,
+    UiLeft,
+    UiRight,
+    UiSelect,
+    UiUp,
 }
 
-/// The set of game controls bound to X52Pro inputs as loaded from a bindings
-/// file.
+impl Control {
+    /// Returns true if the control is used in the given `ControlSet`. The UI
+    /// controls are used for panels and maps in every set.
+    pub fn in_control_set(&self, control_set: ControlSet) -> bool {
+        match self {
+            Control::CycleNextPanel
+            | Control::CyclePreviousPanel
+            | Control::UiBack
+            | Control::UiDown
+            | Control::UiLeft
+            | Control::UiRight
+            | Control::UiSelect
+            | Control::UiUp => true,
+            _ => self.control_set() == control_set,
+        }
+    }
+
+    /// Returns the `ControlSet` the control belongs to.
+    fn control_set(&self) -> ControlSet {
+        match self {
+            Control::HumanoidFlashlight
+            | Control::HumanoidHealthPack
+            | Control::HumanoidNightVision
+            | Control::HumanoidPrimaryFire
+            | Control::HumanoidRechargeTool => ControlSet::OnFoot,
+            Control::SrvDriveAssist
+            | Control::SrvHandbrake
+            | Control::SrvHeadlights
+            | Control::SrvRecallDismissShip
+            | Control::SrvTurret => ControlSet::Srv,
+            _ => ControlSet::Ship,
+        }
+    }
+}
+
+/// A set of game controls that are in use together, e.g. those used when on
+/// foot. The same input can be bound to controls in different sets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ControlSet {
+    Ship,
+    OnFoot,
+    Srv,
+}
+
+/// The set of game controls bound to the inputs of an X52Pro as loaded from a
+/// bindings file.
 #[derive(Debug)]
 pub struct Controls {
     file: ControlBindings,
+    device: String,
 }
 
 impl Controls {
-    /// Returns an instance built by loaded the bindings file at the give path.
-    pub fn from_file(path: &PathBuf) -> Self {
-        Self::from_file_control_bindings(ControlBindings::from_file(path))
+    /// Returns an instance built by loaded the bindings file at the give path,
+    /// taking the bindings for the device with the given name in the file.
+    pub fn from_file(path: &PathBuf, device: &str) -> Self {
+        Self::from_file_control_bindings(ControlBindings::from_file(path), device)
     }
 
-    /// Returns an instance built from the given `ControlBindings` instance.
-    pub fn from_file_control_bindings(file: ControlBindings) -> Self {
-        Controls { file }
+    /// Returns an instance built from the given `ControlBindings` instance,
+    /// taking the bindings for the device with the given name.
+    pub fn from_file_control_bindings(file: ControlBindings, device: &str) -> Self {
+        Controls {
+            file,
+            device: String::from(device),
+        }
     }
 
     /// Returns a vector containing all the `Input` instances that are bound to
@@ -62,28 +138,53 @@ impl Controls {
         let control_binding = match control {
             Control::Boost => &self.file.boost,
             Control::CargoScoop => &self.file.cargo_scoop,
+            Control::CycleFireGroupNext => &self.file.cycle_fire_group_next,
+            Control::CycleFireGroupPrevious => &self.file.cycle_fire_group_previous,
             Control::ExternalLights => &self.file.external_lights,
             Control::Hardpoints => &self.file.hardpoints,
             Control::HeatSink => &self.file.heat_sink,
+            Control::HumanoidFlashlight => &self.file.humanoid_flashlight,
+            Control::HumanoidHealthPack => &self.file.humanoid_health_pack,
+            Control::HumanoidNightVision => &self.file.humanoid_night_vision,
+            Control::HumanoidPrimaryFire => &self.file.humanoid_primary_fire,
+            Control::HumanoidRechargeTool => &self.file.humanoid_recharge_tool,
             Control::Hyperspace => &self.file.hyperspace,
             Control::HyperSuperCombination => &self.file.hyper_super_combo,
+            Control::IncreaseEnginesPower => &self.file.increase_engines_power,
+            Control::IncreaseSystemsPower => &self.file.increase_systems_power,
+            Control::IncreaseWeaponsPower => &self.file.increase_weapons_power,
             Control::LandingGear => &self.file.landing_gear,
             Control::NightVision => &self.file.night_vision,
+            Control::PlayerHudModeToggle => &self.file.player_hud_mode_toggle,
+            Control::ResetPowerDistribution => &self.file.reset_power_distribution,
             Control::SilentRunning => &self.file.silent_running,
+            Control::SrvDriveAssist => &self.file.srv_drive_assist,
+            Control::SrvHandbrake => &self.file.srv_handbrake,
+            Control::SrvHeadlights => &self.file.srv_headlights,
+            Control::SrvRecallDismissShip => &self.file.srv_recall_dismiss_ship,
+            Control::SrvTurret => &self.file.srv_turret,
             Control::Supercruise => &self.file.supercruise,
             Control::Throttle => &self.file.throttle,
+            Control::UiUp => &self.file.ui_up,
+            Control::UiDown => &self.file.ui_down,
+            Control::UiLeft => &self.file.ui_left,
+            Control::UiRight => &self.file.ui_right,
+            Control::UiSelect => &self.file.ui_select,
+            Control::UiBack => &self.file.ui_back,
+            Control::CycleNextPanel => &self.file.cycle_next_panel,
+            Control::CyclePreviousPanel => &self.file.cycle_previous_panel,
         };
 
         let mut inputs = Vec::with_capacity(2);
 
         // This could probably be more elegantly written by mapping the vector
         // elements through the function and collecting the non-None elements.
-        for file_input in vec![
+        for file_input in [
             &control_binding.primary,
             &control_binding.secondary,
             &control_binding.binding,
         ] {
-            if let Some(input) = input_from_file_input(file_input) {
+            if let Some(input) = input_from_file_input(file_input, &self.device) {
                 inputs.push(input);
             }
         }
@@ -92,29 +193,31 @@ impl Controls {
     }
 }
 
-/// Returns a supported X52Pro `Input` that matches the bindings file input.
-fn input_from_file_input(input: &BindingsInput) -> Option<Input> {
-    match input.device.as_str() {
-        X52PRO_DEVICE => match input.name.as_str() {
-            X52PRO_CLUTCH => Some(Input::Clutch),
-            X52PRO_FIRE => Some(Input::Fire),
-            X52PRO_FIRE_A => Some(Input::FireA),
-            X52PRO_FIRE_B => Some(Input::FireB),
-            X52PRO_FIRE_D => Some(Input::FireD),
-            X52PRO_FIRE_E => Some(Input::FireE),
-            X52PRO_POV2_DOWN => Some(Input::PoV2Down),
-            X52PRO_POV2_LEFT => Some(Input::PoV2Left),
-            X52PRO_POV2_RIGHT => Some(Input::PoV2Right),
-            X52PRO_POV2_UP => Some(Input::PoV2Up),
-            X52PRO_T1 => Some(Input::T1),
-            X52PRO_T2 => Some(Input::T2),
-            X52PRO_T3 => Some(Input::T3),
-            X52PRO_T4 => Some(Input::T4),
-            X52PRO_T5 => Some(Input::T5),
-            X52PRO_T6 => Some(Input::T6),
-            X52PRO_Z_AXIS => Some(Input::ZAxis),
-            _ => None,
-        },
+/// Returns a supported X52Pro `Input` that matches the bindings file input on
+/// the device with the given name.
+fn input_from_file_input(input: &BindingsInput, device: &str) -> Option<Input> {
+    if input.device != device {
+        return None;
+    }
+
+    match input.name.as_str() {
+        X52PRO_CLUTCH => Some(Input::Clutch),
+        X52PRO_FIRE => Some(Input::Fire),
+        X52PRO_FIRE_A => Some(Input::FireA),
+        X52PRO_FIRE_B => Some(Input::FireB),
+        X52PRO_FIRE_D => Some(Input::FireD),
+        X52PRO_FIRE_E => Some(Input::FireE),
+        X52PRO_POV2_DOWN => Some(Input::PoV2Down),
+        X52PRO_POV2_LEFT => Some(Input::PoV2Left),
+        X52PRO_POV2_RIGHT => Some(Input::PoV2Right),
+        X52PRO_POV2_UP => Some(Input::PoV2Up),
+        X52PRO_T1 => Some(Input::T1),
+        X52PRO_T2 => Some(Input::T2),
+        X52PRO_T3 => Some(Input::T3),
+        X52PRO_T4 => Some(Input::T4),
+        X52PRO_T5 => Some(Input::T5),
+        X52PRO_T6 => Some(Input::T6),
+        X52PRO_Z_AXIS => Some(Input::ZAxis),
         _ => None,
     }
 }
@@ -142,8 +245,42 @@ mod tests {
             boost: ControlBinding::new((X52PRO_DEVICE, X52PRO_FIRE_D), ("", "")),
             throttle: ControlBinding::new((X52PRO_DEVICE, X52PRO_FIRE_E), ("", "")),
             night_vision: ControlBinding::new((X52PRO_DEVICE, X52PRO_POV2_DOWN), ("", "")),
+            srv_turret: ControlBinding::new((X52PRO_DEVICE, X52PRO_FIRE_B), ("", "")),
+            srv_handbrake: ControlBinding::new((X52PRO_DEVICE, X52PRO_T1), ("", "")),
+            srv_headlights: ControlBinding::new((X52PRO_DEVICE, X52PRO_T4), ("", "")),
+            srv_drive_assist: ControlBinding::new((X52PRO_DEVICE, X52PRO_T5), ("", "")),
+            srv_recall_dismiss_ship: ControlBinding::new(("", ""), (X52PRO_DEVICE, X52PRO_T6)),
+            increase_engines_power: ControlBinding::new(("", ""), (X52PRO_DEVICE, X52PRO_POV2_UP)),
+            increase_systems_power: ControlBinding::new(
+                ("", ""),
+                (X52PRO_DEVICE, X52PRO_POV2_LEFT),
+            ),
+            increase_weapons_power: ControlBinding::new(
+                ("", ""),
+                (X52PRO_DEVICE, X52PRO_POV2_RIGHT),
+            ),
+            reset_power_distribution: ControlBinding::new(("", ""), ("", "")),
+            player_hud_mode_toggle: ControlBinding::new(("", ""), (X52PRO_DEVICE, X52PRO_T5)),
+            ui_up: ControlBinding::new(("", ""), ("", "")),
+            ui_down: ControlBinding::new(("", ""), ("", "")),
+            ui_left: ControlBinding::new(("", ""), ("", "")),
+            ui_right: ControlBinding::new(("", ""), ("", "")),
+            ui_back: ControlBinding::new(("", ""), ("", "")),
+            cycle_next_panel: ControlBinding::new(("", ""), ("", "")),
+            ui_select: ControlBinding::new((X52PRO_DEVICE, X52PRO_CLUTCH), ("", "")),
+            cycle_previous_panel: ControlBinding::new(("", ""), (X52PRO_DEVICE, X52PRO_T6)),
+            cycle_fire_group_next: ControlBinding::new((X52PRO_DEVICE, X52PRO_FIRE_D), ("", "")),
+            cycle_fire_group_previous: ControlBinding::new(
+                (X52PRO_DEVICE, X52PRO_FIRE_E),
+                ("", ""),
+            ),
+            humanoid_flashlight: ControlBinding::new((X52PRO_DEVICE, X52PRO_T2), ("", "")),
+            humanoid_health_pack: ControlBinding::new(("", ""), (X52PRO_DEVICE, X52PRO_T3)),
+            humanoid_night_vision: ControlBinding::new((X52PRO_DEVICE, X52PRO_POV2_DOWN), ("", "")),
+            humanoid_primary_fire: ControlBinding::new((X52PRO_DEVICE, X52PRO_FIRE), ("", "")),
+            humanoid_recharge_tool: ControlBinding::new((X52PRO_DEVICE, X52PRO_FIRE_A), ("", "")),
         };
-        let controls = Controls::from_file_control_bindings(file_control_bindings);
+        let controls = Controls::from_file_control_bindings(file_control_bindings, X52PRO_DEVICE);
 
         assert_eq!(
             controls.inputs_for_control(Control::CargoScoop),
@@ -189,12 +326,108 @@ mod tests {
             controls.inputs_for_control(Control::NightVision),
             vec![Input::PoV2Down]
         );
+        assert_eq!(
+            controls.inputs_for_control(Control::PlayerHudModeToggle),
+            vec![Input::T5]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::UiSelect),
+            vec![Input::Clutch]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::CyclePreviousPanel),
+            vec![Input::T6]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::CycleFireGroupNext),
+            vec![Input::FireD]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::CycleFireGroupPrevious),
+            vec![Input::FireE]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::IncreaseEnginesPower),
+            vec![Input::PoV2Up]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::IncreaseSystemsPower),
+            vec![Input::PoV2Left]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::IncreaseWeaponsPower),
+            vec![Input::PoV2Right]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::ResetPowerDistribution),
+            vec![]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::SrvTurret),
+            vec![Input::FireB]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::SrvHandbrake),
+            vec![Input::T1]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::SrvHeadlights),
+            vec![Input::T4]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::SrvDriveAssist),
+            vec![Input::T5]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::SrvRecallDismissShip),
+            vec![Input::T6]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::HumanoidFlashlight),
+            vec![Input::T2]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::HumanoidHealthPack),
+            vec![Input::T3]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::HumanoidNightVision),
+            vec![Input::PoV2Down]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::HumanoidPrimaryFire),
+            vec![Input::Fire]
+        );
+        assert_eq!(
+            controls.inputs_for_control(Control::HumanoidRechargeTool),
+            vec![Input::FireA]
+        );
+    }
+
+    #[test]
+    fn control_control_set() {
+        assert_eq!(Control::LandingGear.control_set(), ControlSet::Ship);
+        assert_eq!(
+            Control::HumanoidFlashlight.control_set(),
+            ControlSet::OnFoot
+        );
+        assert_eq!(Control::SrvTurret.control_set(), ControlSet::Srv);
+    }
+
+    #[test]
+    fn control_in_control_set() {
+        assert!(Control::LandingGear.in_control_set(ControlSet::Ship));
+        assert!(!Control::LandingGear.in_control_set(ControlSet::OnFoot));
+        assert!(Control::SrvTurret.in_control_set(ControlSet::Srv));
+        for control_set in [ControlSet::Ship, ControlSet::OnFoot, ControlSet::Srv] {
+            assert!(Control::UiSelect.in_control_set(control_set));
+        }
     }
 
     #[test]
     fn input_from_file_input_returns_optional_inputs_given_a_file_input() {
         fn call_with(device: &str, name: &str) -> Option<Input> {
-            input_from_file_input(&BindingsInput::new(device, name))
+            input_from_file_input(&BindingsInput::new(device, name), X52PRO_DEVICE)
         }
 
         assert_eq!(call_with(X52PRO_DEVICE, X52PRO_CLUTCH), Some(Input::Clutch));
@@ -227,5 +460,9 @@ mod tests {
         assert_eq!(call_with(X52PRO_DEVICE, X52PRO_Z_AXIS), Some(Input::ZAxis));
         assert_eq!(call_with(X52PRO_DEVICE, "Other"), None);
         assert_eq!(call_with("Other", X52PRO_T2), None);
+        assert_eq!(
+            input_from_file_input(&BindingsInput::new("Other", X52PRO_T2), "Other"),
+            Some(Input::T2)
+        );
     }
 }
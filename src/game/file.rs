@@ -1,25 +1,69 @@
 mod control_bindings;
 pub mod journal;
+pub mod session;
 
 pub use control_bindings::*;
 use glob::glob;
 use log::debug;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+// Under Proton the game sees a Windows user profile inside the prefix for its
+// Steam app id.
+#[cfg(target_os = "linux")]
+const PROTON_USER_PROFILE_PATH: &str =
+    "Steam/steamapps/compatdata/359320/pfx/drive_c/users/steamuser";
+
+/// Returns a `PathBuf` for the user profile directory as seen by the game.
+#[cfg(windows)]
+fn user_profile_dir_path() -> PathBuf {
+    dirs::home_dir().expect("Can't find user home directory")
+}
+
+/// Returns a `PathBuf` for the user profile directory as seen by the game,
+/// which is inside the game's Proton prefix.
+#[cfg(target_os = "linux")]
+fn user_profile_dir_path() -> PathBuf {
+    dirs::data_dir()
+        .expect("Can't find user data directory")
+        .join(PROTON_USER_PROFILE_PATH)
+}
+
+/// Returns a `PathBuf` for the user's local application data directory as seen
+/// by the game.
+#[cfg(windows)]
+fn local_app_data_dir_path() -> PathBuf {
+    dirs::data_local_dir().expect("Can't find user app data directory")
+}
+
+/// Returns a `PathBuf` for the user's local application data directory as seen
+/// by the game, which is inside the game's Proton prefix.
+#[cfg(target_os = "linux")]
+fn local_app_data_dir_path() -> PathBuf {
+    user_profile_dir_path().join("AppData").join("Local")
+}
+
+/// Returns a `PathBuf` for the directory containing the game's bindings files.
+pub fn bindings_dir_path() -> PathBuf {
+    local_app_data_dir_path()
+        .join("Frontier Developments")
+        .join("Elite Dangerous")
+        .join("Options")
+        .join("Bindings")
+}
+
 /// Returns a `PathBuf` for the directory containing the game's journal files.
 pub fn journal_dir_path() -> PathBuf {
-    dirs::home_dir()
-        .expect("Can't find user home directory")
-        .join(r#"Saved Games\Frontier Developments\Elite Dangerous"#) // TODO const & use below
+    user_profile_dir_path()
+        .join("Saved Games")
+        .join("Frontier Developments")
+        .join("Elite Dangerous")
 }
 
 /// Optionally returns a `PathBuf` for the latest journal file if one is found.
 pub fn latest_journal_file_path() -> Option<PathBuf> {
-    let journal_file_pattern = dirs::home_dir()
-        .expect("Can't find user home directory")
-        .join(r#"Saved Games\Frontier Developments\Elite Dangerous\Journal*.log"#);
+    let journal_file_pattern = journal_dir_path().join("Journal*.log");
     let journal_file_pattern = journal_file_pattern
         .to_str()
         .expect("Can't convert user home directory to UTF-8");
@@ -38,18 +82,36 @@ pub fn latest_journal_file_path() -> Option<PathBuf> {
 }
 
 pub fn status_file_path() -> PathBuf {
-    dirs::home_dir()
-        .expect("Can't find user home directory")
-        .join(r#"Saved Games\Frontier Developments\Elite Dangerous\Status.json"#)
+    journal_dir_path().join("Status.json")
 }
 
-#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 pub struct Status {
     #[serde(rename = "Flags")]
     pub flags: u32,
+    #[serde(rename = "Flags2")]
+    pub flags2: u32,
     #[serde(rename = "LegalState")]
     pub legal_state: LegalState,
+    #[serde(rename = "Fuel", skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<Fuel>,
+    #[serde(rename = "Cargo", skip_serializing_if = "Option::is_none")]
+    pub cargo: Option<f64>,
+    #[serde(rename = "Pips", skip_serializing_if = "Option::is_none")]
+    pub pips: Option<Pips>,
+    #[serde(rename = "FireGroup", skip_serializing_if = "Option::is_none")]
+    pub fire_group: Option<u8>,
+    #[serde(rename = "GuiFocus", skip_serializing_if = "Option::is_none")]
+    pub gui_focus: Option<GuiFocus>,
+    #[serde(rename = "Heading", skip_serializing_if = "Option::is_none")]
+    pub heading: Option<f64>,
+    #[serde(rename = "Altitude", skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<f64>,
+    #[serde(rename = "Oxygen", skip_serializing_if = "Option::is_none")]
+    pub oxygen: Option<f64>,
+    #[serde(rename = "Health", skip_serializing_if = "Option::is_none")]
+    pub health: Option<f64>,
 }
 
 impl Status {
@@ -59,7 +121,7 @@ impl Status {
         let json = fs::read_to_string(path).expect("Could not read status file");
 
         // When exiting the game temporarily writes an empty file.
-        if json == "" {
+        if json.is_empty() {
             debug!("Status file empty");
             None
         } else {
@@ -73,16 +135,104 @@ impl Status {
     }
 }
 
-#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub enum LegalState {
     Speeding,
     #[serde(other)]
+    #[default]
     Other,
 }
 
-impl Default for LegalState {
-    fn default() -> Self {
-        LegalState::Other
+/// The fuel levels in tons.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Fuel {
+    #[serde(rename = "FuelMain")]
+    pub main: f64,
+    #[serde(rename = "FuelReservoir")]
+    pub reservoir: f64,
+}
+
+/// The power distributor settings in half pips, as written to the file.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(from = "[u8; 3]", into = "[u8; 3]")]
+pub struct Pips {
+    pub systems: u8,
+    pub engines: u8,
+    pub weapons: u8,
+}
+
+impl From<[u8; 3]> for Pips {
+    fn from(pips: [u8; 3]) -> Self {
+        Self {
+            systems: pips[0],
+            engines: pips[1],
+            weapons: pips[2],
+        }
+    }
+}
+
+impl From<Pips> for [u8; 3] {
+    fn from(pips: Pips) -> Self {
+        [pips.systems, pips.engines, pips.weapons]
+    }
+}
+
+/// The panel or screen that currently has focus.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(from = "u8", into = "u8")]
+pub enum GuiFocus {
+    NoFocus,
+    InternalPanel,
+    ExternalPanel,
+    CommsPanel,
+    RolePanel,
+    StationServices,
+    GalaxyMap,
+    SystemMap,
+    Orrery,
+    FullSpectrumScanner,
+    SurfaceAreaAnalysis,
+    Codex,
+    Other(u8),
+}
+
+impl From<u8> for GuiFocus {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => GuiFocus::NoFocus,
+            1 => GuiFocus::InternalPanel,
+            2 => GuiFocus::ExternalPanel,
+            3 => GuiFocus::CommsPanel,
+            4 => GuiFocus::RolePanel,
+            5 => GuiFocus::StationServices,
+            6 => GuiFocus::GalaxyMap,
+            7 => GuiFocus::SystemMap,
+            8 => GuiFocus::Orrery,
+            9 => GuiFocus::FullSpectrumScanner,
+            10 => GuiFocus::SurfaceAreaAnalysis,
+            11 => GuiFocus::Codex,
+            value => GuiFocus::Other(value),
+        }
+    }
+}
+
+impl From<GuiFocus> for u8 {
+    fn from(gui_focus: GuiFocus) -> Self {
+        match gui_focus {
+            GuiFocus::NoFocus => 0,
+            GuiFocus::InternalPanel => 1,
+            GuiFocus::ExternalPanel => 2,
+            GuiFocus::CommsPanel => 3,
+            GuiFocus::RolePanel => 4,
+            GuiFocus::StationServices => 5,
+            GuiFocus::GalaxyMap => 6,
+            GuiFocus::SystemMap => 7,
+            GuiFocus::Orrery => 8,
+            GuiFocus::FullSpectrumScanner => 9,
+            GuiFocus::SurfaceAreaAnalysis => 10,
+            GuiFocus::Codex => 11,
+            GuiFocus::Other(value) => value,
+        }
     }
 }
 
@@ -100,7 +250,9 @@ mod tests {
             Status::from_json(json),
             Status {
                 flags: 4,
-                legal_state: LegalState::Speeding
+                flags2: 0,
+                legal_state: LegalState::Speeding,
+                ..Default::default()
             }
         );
     }
@@ -111,4 +263,51 @@ mod tests {
             String::from(r#"{"timestamp": "2021-08-21T21:36:35Z", "event": "Status", "Flags": 0}"#);
         assert_eq!(Status::from_json(json).legal_state, LegalState::Other);
     }
+
+    #[test]
+    fn status_from_json_parses_flags2() {
+        let json = String::from(
+            r#"{"timestamp": "2021-08-21T21:36:35Z", "event": "Status", "Flags": 0, "Flags2": 65537}"#,
+        );
+        assert_eq!(Status::from_json(json).flags2, 65537);
+    }
+
+    #[test]
+    fn status_from_json_parses_numeric_fields() {
+        let json = String::from(
+            r#"{ "timestamp":"2021-08-21T21:36:35Z", "event":"Status", "Flags":16842765, "Pips":[4,8,0], "FireGroup":1, "GuiFocus":6, "Fuel":{ "FuelMain":8.5, "FuelReservoir":0.46 }, "Cargo":2.0, "LegalState":"Clean", "Latitude":1.0, "Longitude":2.0, "Heading":270, "Altitude":1500, "Oxygen":0.5, "Health":0.75 }"#,
+        );
+
+        assert_eq!(
+            Status::from_json(json),
+            Status {
+                flags: 16842765,
+                flags2: 0,
+                legal_state: LegalState::Other,
+                fuel: Some(Fuel {
+                    main: 8.5,
+                    reservoir: 0.46
+                }),
+                cargo: Some(2.0),
+                pips: Some(Pips {
+                    systems: 4,
+                    engines: 8,
+                    weapons: 0
+                }),
+                fire_group: Some(1),
+                gui_focus: Some(GuiFocus::GalaxyMap),
+                heading: Some(270.0),
+                altitude: Some(1500.0),
+                oxygen: Some(0.5),
+                health: Some(0.75),
+            }
+        );
+    }
+
+    #[test]
+    fn gui_focus_round_trips_unknown_values() {
+        assert_eq!(GuiFocus::from(42), GuiFocus::Other(42));
+        assert_eq!(u8::from(GuiFocus::Other(42)), 42);
+        assert_eq!(u8::from(GuiFocus::from(10)), 10);
+    }
 }
@@ -31,6 +31,58 @@ pub struct ControlBindings {
     pub throttle: ControlBinding,
     #[serde(rename = "NightVisionToggle")]
     pub night_vision: ControlBinding,
+    #[serde(rename = "ToggleBuggyTurretButton")]
+    pub srv_turret: ControlBinding,
+    #[serde(rename = "AutoBreakBuggyButton")]
+    pub srv_handbrake: ControlBinding,
+    #[serde(rename = "HeadlightsBuggyButton")]
+    pub srv_headlights: ControlBinding,
+    #[serde(rename = "ToggleDriveAssist")]
+    pub srv_drive_assist: ControlBinding,
+    #[serde(rename = "RecallDismissShip")]
+    pub srv_recall_dismiss_ship: ControlBinding,
+    #[serde(rename = "IncreaseEnginesPower")]
+    pub increase_engines_power: ControlBinding,
+    #[serde(rename = "IncreaseSystemsPower")]
+    pub increase_systems_power: ControlBinding,
+    #[serde(rename = "IncreaseWeaponsPower")]
+    pub increase_weapons_power: ControlBinding,
+    #[serde(rename = "ResetPowerDistribution")]
+    pub reset_power_distribution: ControlBinding,
+    #[serde(rename = "CycleFireGroupNext")]
+    pub cycle_fire_group_next: ControlBinding,
+    #[serde(rename = "CycleFireGroupPrevious")]
+    pub cycle_fire_group_previous: ControlBinding,
+    #[serde(rename = "PlayerHUDModeToggle")]
+    pub player_hud_mode_toggle: ControlBinding,
+    #[serde(rename = "UI_Up")]
+    pub ui_up: ControlBinding,
+    #[serde(rename = "UI_Down")]
+    pub ui_down: ControlBinding,
+    #[serde(rename = "UI_Left")]
+    pub ui_left: ControlBinding,
+    #[serde(rename = "UI_Right")]
+    pub ui_right: ControlBinding,
+    #[serde(rename = "UI_Select")]
+    pub ui_select: ControlBinding,
+    #[serde(rename = "UI_Back")]
+    pub ui_back: ControlBinding,
+    #[serde(rename = "CycleNextPanel")]
+    pub cycle_next_panel: ControlBinding,
+    #[serde(rename = "CyclePreviousPanel")]
+    pub cycle_previous_panel: ControlBinding,
+    // On-foot controls are optional as they are missing from Horizons bindings
+    // files.
+    #[serde(rename = "HumanoidToggleFlashlightButton", default)]
+    pub humanoid_flashlight: ControlBinding,
+    #[serde(rename = "HumanoidHealthPack", default)]
+    pub humanoid_health_pack: ControlBinding,
+    #[serde(rename = "HumanoidToggleNightVisionButton", default)]
+    pub humanoid_night_vision: ControlBinding,
+    #[serde(rename = "HumanoidPrimaryFireButton", default)]
+    pub humanoid_primary_fire: ControlBinding,
+    #[serde(rename = "HumanoidSwitchToRechargeTool", default)]
+    pub humanoid_recharge_tool: ControlBinding,
 }
 
 impl ControlBindings {
@@ -143,6 +195,106 @@ mod tests {
                     <Primary Device="D22" Key="K22" />
                     <Secondary Device="D23" Key="K23" />
                 </NightVisionToggle>
+                <ToggleBuggyTurretButton>
+                    <Primary Device="D34" Key="K34" />
+                    <Secondary Device="D35" Key="K35" />
+                </ToggleBuggyTurretButton>
+                <AutoBreakBuggyButton>
+                    <Primary Device="D36" Key="K36" />
+                    <Secondary Device="D37" Key="K37" />
+                </AutoBreakBuggyButton>
+                <HeadlightsBuggyButton>
+                    <Primary Device="D38" Key="K38" />
+                    <Secondary Device="D39" Key="K39" />
+                </HeadlightsBuggyButton>
+                <ToggleDriveAssist>
+                    <Primary Device="D40" Key="K40" />
+                    <Secondary Device="D41" Key="K41" />
+                </ToggleDriveAssist>
+                <RecallDismissShip>
+                    <Primary Device="D42" Key="K42" />
+                    <Secondary Device="D43" Key="K43" />
+                </RecallDismissShip>
+                <IncreaseEnginesPower>
+                    <Primary Device="D44" Key="K44" />
+                    <Secondary Device="D45" Key="K45" />
+                </IncreaseEnginesPower>
+                <IncreaseSystemsPower>
+                    <Primary Device="D46" Key="K46" />
+                    <Secondary Device="D47" Key="K47" />
+                </IncreaseSystemsPower>
+                <IncreaseWeaponsPower>
+                    <Primary Device="D48" Key="K48" />
+                    <Secondary Device="D49" Key="K49" />
+                </IncreaseWeaponsPower>
+                <ResetPowerDistribution>
+                    <Primary Device="D50" Key="K50" />
+                    <Secondary Device="D51" Key="K51" />
+                </ResetPowerDistribution>
+                <CycleFireGroupNext>
+                    <Primary Device="D52" Key="K52" />
+                    <Secondary Device="D53" Key="K53" />
+                </CycleFireGroupNext>
+                <CycleFireGroupPrevious>
+                    <Primary Device="D54" Key="K54" />
+                    <Secondary Device="D55" Key="K55" />
+                </CycleFireGroupPrevious>
+                <PlayerHUDModeToggle>
+                    <Primary Device="D72" Key="K72" />
+                    <Secondary Device="D73" Key="K73" />
+                </PlayerHUDModeToggle>
+                <UI_Up>
+                    <Primary Device="D56" Key="K56" />
+                    <Secondary Device="D57" Key="K57" />
+                </UI_Up>
+                <UI_Down>
+                    <Primary Device="D58" Key="K58" />
+                    <Secondary Device="D59" Key="K59" />
+                </UI_Down>
+                <UI_Left>
+                    <Primary Device="D60" Key="K60" />
+                    <Secondary Device="D61" Key="K61" />
+                </UI_Left>
+                <UI_Right>
+                    <Primary Device="D62" Key="K62" />
+                    <Secondary Device="D63" Key="K63" />
+                </UI_Right>
+                <UI_Select>
+                    <Primary Device="D64" Key="K64" />
+                    <Secondary Device="D65" Key="K65" />
+                </UI_Select>
+                <UI_Back>
+                    <Primary Device="D66" Key="K66" />
+                    <Secondary Device="D67" Key="K67" />
+                </UI_Back>
+                <CycleNextPanel>
+                    <Primary Device="D68" Key="K68" />
+                    <Secondary Device="D69" Key="K69" />
+                </CycleNextPanel>
+                <CyclePreviousPanel>
+                    <Primary Device="D70" Key="K70" />
+                    <Secondary Device="D71" Key="K71" />
+                </CyclePreviousPanel>
+                <HumanoidToggleFlashlightButton>
+                    <Primary Device="D24" Key="K24" />
+                    <Secondary Device="D25" Key="K25" />
+                </HumanoidToggleFlashlightButton>
+                <HumanoidHealthPack>
+                    <Primary Device="D26" Key="K26" />
+                    <Secondary Device="D27" Key="K27" />
+                </HumanoidHealthPack>
+                <HumanoidToggleNightVisionButton>
+                    <Primary Device="D28" Key="K28" />
+                    <Secondary Device="D29" Key="K29" />
+                </HumanoidToggleNightVisionButton>
+                <HumanoidPrimaryFireButton>
+                    <Primary Device="D30" Key="K30" />
+                    <Secondary Device="D31" Key="K31" />
+                </HumanoidPrimaryFireButton>
+                <HumanoidSwitchToRechargeTool>
+                    <Primary Device="D32" Key="K32" />
+                    <Secondary Device="D33" Key="K33" />
+                </HumanoidSwitchToRechargeTool>
             </Root>
             "#,
         );
@@ -166,6 +318,31 @@ mod tests {
                 ..Default::default()
             },
             night_vision: ControlBinding::new(("D22", "K22"), ("D23", "K23")),
+            srv_turret: ControlBinding::new(("D34", "K34"), ("D35", "K35")),
+            srv_handbrake: ControlBinding::new(("D36", "K36"), ("D37", "K37")),
+            srv_headlights: ControlBinding::new(("D38", "K38"), ("D39", "K39")),
+            srv_drive_assist: ControlBinding::new(("D40", "K40"), ("D41", "K41")),
+            srv_recall_dismiss_ship: ControlBinding::new(("D42", "K42"), ("D43", "K43")),
+            increase_engines_power: ControlBinding::new(("D44", "K44"), ("D45", "K45")),
+            increase_systems_power: ControlBinding::new(("D46", "K46"), ("D47", "K47")),
+            increase_weapons_power: ControlBinding::new(("D48", "K48"), ("D49", "K49")),
+            reset_power_distribution: ControlBinding::new(("D50", "K50"), ("D51", "K51")),
+            cycle_fire_group_next: ControlBinding::new(("D52", "K52"), ("D53", "K53")),
+            cycle_fire_group_previous: ControlBinding::new(("D54", "K54"), ("D55", "K55")),
+            player_hud_mode_toggle: ControlBinding::new(("D72", "K72"), ("D73", "K73")),
+            ui_up: ControlBinding::new(("D56", "K56"), ("D57", "K57")),
+            ui_down: ControlBinding::new(("D58", "K58"), ("D59", "K59")),
+            ui_left: ControlBinding::new(("D60", "K60"), ("D61", "K61")),
+            ui_right: ControlBinding::new(("D62", "K62"), ("D63", "K63")),
+            ui_select: ControlBinding::new(("D64", "K64"), ("D65", "K65")),
+            ui_back: ControlBinding::new(("D66", "K66"), ("D67", "K67")),
+            cycle_next_panel: ControlBinding::new(("D68", "K68"), ("D69", "K69")),
+            cycle_previous_panel: ControlBinding::new(("D70", "K70"), ("D71", "K71")),
+            humanoid_flashlight: ControlBinding::new(("D24", "K24"), ("D25", "K25")),
+            humanoid_health_pack: ControlBinding::new(("D26", "K26"), ("D27", "K27")),
+            humanoid_night_vision: ControlBinding::new(("D28", "K28"), ("D29", "K29")),
+            humanoid_primary_fire: ControlBinding::new(("D30", "K30"), ("D31", "K31")),
+            humanoid_recharge_tool: ControlBinding::new(("D32", "K32"), ("D33", "K33")),
         };
 
         assert_eq!(ControlBindings::from_str(xml), expected);
@@ -1,12 +1,13 @@
 use crate::events;
 use hotwatch::Hotwatch;
-use log::{debug, info};
-use serde::Deserialize;
+use log::{debug, info, warn};
+use serde::{Deserialize, Deserializer};
 use std::{
     fs::File,
     io::{BufRead, BufReader},
     path::PathBuf,
     sync::mpsc::Sender,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 /// Watch the given directory for new journal files then send a
@@ -37,7 +38,7 @@ pub fn watch_dir(dir_path: PathBuf, watcher: &mut Hotwatch, tx: &Sender<events::
 }
 
 /// A stateful reader that can be called repeatedly, each time returning only
-/// the new journal events appended to journal file since the last call.
+/// the new journal lines appended to journal file since the last call.
 pub struct JournalReader {
     journal_buf_reader: Option<BufReader<File>>,
 }
@@ -59,25 +60,30 @@ impl JournalReader {
     }
 
     /// When called before `open` returns an empty vector. When called the first
-    /// time after `open` returns all the journal events currently in the
-    /// journal. On subsequent calls returns the new journal events appended to
-    /// journal file since the last call.
-    pub fn new_events(&mut self) -> Vec<Event> {
+    /// time after `open` returns all the lines currently in the journal. On
+    /// subsequent calls returns the new lines appended to journal file since
+    /// the last call.
+    pub fn new_lines(&mut self) -> Vec<String> {
         if let Some(reader) = &mut self.journal_buf_reader {
-            events_from_buf_reader(reader, event_from_json)
+            lines_from_buf_reader(reader)
         } else {
             vec![]
         }
     }
 }
 
-/// Read lines from the given reader and map to journal events using the given
-/// parser, filtering out `Event::Other`.
-fn events_from_buf_reader<T>(reader: &mut BufReader<T>, parser: fn(&str) -> Event) -> Vec<Event>
+/// Returns the journal events parsed from the given lines, filtering out
+/// `Event::Other`.
+pub fn events_from_lines(lines: &[String]) -> Vec<Event> {
+    events_from_lines_with_parser(lines, event_from_json)
+}
+
+/// Read the available lines from the given reader.
+fn lines_from_buf_reader<T>(reader: &mut BufReader<T>) -> Vec<String>
 where
     T: std::io::Read,
 {
-    let mut events = Vec::new();
+    let mut lines = Vec::new();
     let mut line = String::new();
 
     while reader
@@ -85,17 +91,29 @@ where
         .expect("Can't read journal file")
         != 0
     {
-        match parser(&line) {
+        lines.push(line.clone());
+
+        // The `read_line` call above *appends* to the string but we want to
+        // read one line at a time.
+        line.clear();
+    }
+
+    lines
+}
+
+/// Map lines to journal events using the given parser, filtering out
+/// `Event::Other`.
+fn events_from_lines_with_parser(lines: &[String], parser: fn(&str) -> Event) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    for line in lines {
+        match parser(line) {
             Event::Other => (),
             event => {
                 info!("Journal event {:?}", event);
                 events.push(event);
             }
         }
-
-        // The `read_line` call above *appends* to the string but we want to
-        // parse one line at a time.
-        line.clear();
     }
 
     events
@@ -103,7 +121,70 @@ where
 
 /// Returns a journal event parsed from the given JSON string.
 fn event_from_json(json: &str) -> Event {
-    serde_json::from_str(&json).expect("Can't parse journal event JSON")
+    serde_json::from_str(json).expect("Can't parse journal event JSON")
+}
+
+/// Deserializes a journal timestamp, logging it and giving `None` if it isn't
+/// in the journal format so the rest of the event is still used.
+fn system_time_from_timestamp<'de, D>(deserializer: D) -> Result<Option<SystemTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let timestamp = String::deserialize(deserializer)?;
+    let time = parse_timestamp(&timestamp);
+    if time.is_none() {
+        warn!("Ignoring invalid journal timestamp {:?}", timestamp);
+    }
+    Ok(time)
+}
+
+/// Returns the time given by a journal timestamp such as
+/// `2021-05-14T00:00:00Z`, which is always UTC, or `None` if it isn't in that
+/// format.
+fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let bytes = timestamp.as_bytes();
+    if bytes.len() != 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || bytes[10] != b'T'
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+        || bytes[19] != b'Z'
+    {
+        return None;
+    }
+
+    let field = |start: usize, end: usize| -> Option<u64> {
+        let digits = &timestamp[start..end];
+        if digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            digits.parse().ok()
+        } else {
+            None
+        }
+    };
+    let (year, month, day) = (field(0, 4)?, field(5, 7)?, field(8, 10)?);
+    let (hour, minute, second) = (field(11, 13)?, field(14, 16)?, field(17, 19)?);
+
+    if year < 1970
+        || !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    // Count the days from the epoch using years that start in March so the
+    // leap day falls at the end of the year.
+    let year = if month <= 2 { year - 1 } else { year };
+    let (era, year_of_era) = (year / 400, year % 400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(seconds))
 }
 
 // This enum should be renamed `JournalEvent` to reduce name collisions outside
@@ -111,25 +192,105 @@ fn event_from_json(json: &str) -> Event {
 #[derive(Deserialize, Debug, PartialEq)]
 #[serde(tag = "event")]
 pub enum Event {
+    CarrierJump,
+    CarrierJumpCancelled,
+    CarrierJumpRequest {
+        #[serde(
+            rename = "DepartureTime",
+            default,
+            deserialize_with = "system_time_from_timestamp"
+        )]
+        departure_time: Option<SystemTime>,
+    },
+    CrewMemberJoins,
+    CrewMemberQuits,
+    DockFighter,
     Docked,
     DockingCancelled,
-    DockingGranted,
+    DockingGranted {
+        #[serde(rename = "LandingPad", default)]
+        landing_pad: Option<u32>,
+        #[serde(default, deserialize_with = "system_time_from_timestamp")]
+        timestamp: Option<SystemTime>,
+    },
     DockingTimeout,
+    EscapeInterdiction,
+    #[serde(rename = "FSDJump")]
+    FsdJump {
+        #[serde(rename = "StarSystem")]
+        star_system: String,
+    },
+    #[serde(rename = "FSDTarget")]
+    FsdTarget {
+        #[serde(rename = "Name")]
+        name: String,
+    },
+    FuelScoop,
+    HeatSinkLaunched,
+    HeatWarning,
+    HullDamage,
+    Interdicted,
+    Interdiction,
+    JoinACrew,
+    LaunchFighter {
+        #[serde(rename = "PlayerControlled")]
+        player_controlled: bool,
+    },
+    Location {
+        #[serde(rename = "StarSystem")]
+        star_system: String,
+    },
+    Loadout {
+        #[serde(rename = "FuelCapacity", default)]
+        fuel_capacity: Option<FuelCapacity>,
+    },
+    QuitACrew,
+    #[serde(rename = "SAAScanComplete")]
+    SaaScanComplete,
+    Scan,
+    ShieldState {
+        #[serde(rename = "ShieldsUp")]
+        shields_up: bool,
+    },
+    UnderAttack,
     #[serde(other)]
     Other,
 }
 
+/// The fuel tank capacities in tons.
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+pub struct FuelCapacity {
+    #[serde(rename = "Main")]
+    pub main: f64,
+    #[serde(rename = "Reserve")]
+    pub reserve: f64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn events_from_buf_reader_maps_each_line_to_an_event() {
-        let events = "LINE1\nLINE2\n".as_bytes();
-        let mut reader = BufReader::new(events);
+    fn lines_from_buf_reader_returns_each_line() {
+        let mut reader = BufReader::new("LINE1\nLINE2\n".as_bytes());
+
+        assert_eq!(
+            lines_from_buf_reader(&mut reader),
+            vec![String::from("LINE1\n"), String::from("LINE2\n")]
+        );
+        assert_eq!(lines_from_buf_reader(&mut reader), Vec::<String>::new());
+    }
+
+    #[test]
+    fn events_from_lines_maps_each_line_to_an_event() {
+        let mut reader = BufReader::new("LINE1\nLINE2\n".as_bytes());
+        let lines = lines_from_buf_reader(&mut reader);
         fn fake_parser(json: &str) -> Event {
             match json {
-                "LINE1\n" => Event::DockingGranted,
+                "LINE1\n" => Event::DockingGranted {
+                    landing_pad: None,
+                    timestamp: None,
+                },
                 "LINE2\n" => Event::Other,
                 _ => panic!("Unexpected line value passed to parser '{}'", json),
             }
@@ -137,8 +298,11 @@ mod tests {
 
         // Filters out `Event::Other`.
         assert_eq!(
-            events_from_buf_reader(&mut reader, fake_parser),
-            vec![Event::DockingGranted]
+            events_from_lines_with_parser(&lines, fake_parser),
+            vec![Event::DockingGranted {
+                landing_pad: None,
+                timestamp: None
+            }]
         );
     }
 
@@ -160,7 +324,10 @@ mod tests {
             event_from_json(
                 r#"{ "timestamp":"2021-05-12T00:00:00Z", "event":"DockingGranted", "LandingPad":1, "MarketID":1, "StationName":"A", "StationType":"B" }"#
             ),
-            Event::DockingGranted
+            Event::DockingGranted {
+                landing_pad: Some(1),
+                timestamp: Some(UNIX_EPOCH + Duration::from_secs(1_620_777_600))
+            }
         );
         assert_eq!(
             event_from_json(
@@ -168,6 +335,163 @@ mod tests {
             ),
             Event::DockingTimeout
         );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"LaunchFighter", "Loadout":"starter", "ID":1, "PlayerControlled":true }"#
+            ),
+            Event::LaunchFighter {
+                player_controlled: true
+            }
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"Loadout", "Ship":"asp", "ShipID":1, "FuelCapacity":{ "Main":32.0, "Reserve":0.63 }, "Modules":[] }"#
+            ),
+            Event::Loadout {
+                fuel_capacity: Some(FuelCapacity {
+                    main: 32.0,
+                    reserve: 0.63
+                })
+            }
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"FuelScoop", "Scooped":5.0, "Total":32.0 }"#
+            ),
+            Event::FuelScoop
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"DockFighter", "ID":1 }"#
+            ),
+            Event::DockFighter
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"CrewMemberJoins", "Crew":"A" }"#
+            ),
+            Event::CrewMemberJoins
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"CrewMemberQuits", "Crew":"A" }"#
+            ),
+            Event::CrewMemberQuits
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"JoinACrew", "Captain":"A" }"#
+            ),
+            Event::JoinACrew
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"QuitACrew", "Captain":"A" }"#
+            ),
+            Event::QuitACrew
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"Interdicted", "Submitted":false, "Interdictor":"A", "IsPlayer":false }"#
+            ),
+            Event::Interdicted
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"Interdiction", "Success":true, "Interdicted":"A", "IsPlayer":false }"#
+            ),
+            Event::Interdiction
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"EscapeInterdiction", "Interdictor":"A", "IsPlayer":false }"#
+            ),
+            Event::EscapeInterdiction
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"UnderAttack", "Target":"You" }"#
+            ),
+            Event::UnderAttack
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"ShieldState", "ShieldsUp":false }"#
+            ),
+            Event::ShieldState { shields_up: false }
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"HullDamage", "Health":0.8, "PlayerPilot":true, "Fighter":false }"#
+            ),
+            Event::HullDamage
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"HeatSinkLaunched" }"#
+            ),
+            Event::HeatSinkLaunched
+        );
+        assert_eq!(
+            event_from_json(r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"HeatWarning" }"#),
+            Event::HeatWarning
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"FSDJump", "StarSystem":"A", "SystemAddress":1, "StarPos":[0.0,0.0,0.0], "JumpDist":9.5, "FuelUsed":1.2, "FuelLevel":30.0 }"#
+            ),
+            Event::FsdJump {
+                star_system: String::from("A")
+            }
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"FSDTarget", "Name":"B", "SystemAddress":2, "StarClass":"K", "RemainingJumpsInRoute":1 }"#
+            ),
+            Event::FsdTarget {
+                name: String::from("B")
+            }
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"Location", "Docked":false, "StarSystem":"C", "SystemAddress":3, "StarPos":[0.0,0.0,0.0] }"#
+            ),
+            Event::Location {
+                star_system: String::from("C")
+            }
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"Scan", "ScanType":"Detailed", "BodyName":"A 1", "BodyID":1, "DistanceFromArrivalLS":500.0 }"#
+            ),
+            Event::Scan
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"SAAScanComplete", "BodyName":"A 1", "BodyID":1, "ProbesUsed":5, "EfficiencyTarget":6 }"#
+            ),
+            Event::SaaScanComplete
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"CarrierJumpRequest", "CarrierID":1, "SystemName":"A", "SystemAddress":1, "DepartureTime":"2021-05-15T00:15:10Z" }"#
+            ),
+            Event::CarrierJumpRequest {
+                departure_time: Some(UNIX_EPOCH + Duration::from_secs(1_621_037_710))
+            }
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"CarrierJumpCancelled", "CarrierID":1 }"#
+            ),
+            Event::CarrierJumpCancelled
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:15:10Z", "event":"CarrierJump", "Docked":true, "StarSystem":"A", "SystemAddress":1, "StarPos":[0.0,0.0,0.0] }"#
+            ),
+            Event::CarrierJump
+        );
         assert_eq!(
             event_from_json(
                 r#"{ "timestamp":"2021-05-12T00:00:00Z", "event":"Music", "MusicTrack":"NoTrack" }"#
@@ -175,4 +499,46 @@ mod tests {
             Event::Other
         );
     }
+
+    #[test]
+    fn invalid_timestamp_is_ignored() {
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"yesterday", "event":"DockingGranted", "LandingPad":1, "MarketID":1, "StationName":"A", "StationType":"B" }"#
+            ),
+            Event::DockingGranted {
+                landing_pad: Some(1),
+                timestamp: None
+            }
+        );
+        assert_eq!(
+            event_from_json(
+                r#"{ "timestamp":"2021-05-15T00:00:00Z", "event":"CarrierJumpRequest", "CarrierID":1, "SystemName":"A", "SystemAddress":1, "DepartureTime":"soon" }"#
+            ),
+            Event::CarrierJumpRequest {
+                departure_time: None
+            }
+        );
+    }
+
+    #[test]
+    fn parse_timestamp_returns_utc_time() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(UNIX_EPOCH));
+        assert_eq!(
+            parse_timestamp("2024-02-29T23:59:59Z"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_251_199))
+        );
+        assert_eq!(
+            parse_timestamp("2021-12-31T12:00:00Z"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_640_952_000))
+        );
+    }
+
+    #[test]
+    fn parse_timestamp_rejects_other_formats() {
+        assert_eq!(parse_timestamp("2021-05-14 00:00:00Z"), None);
+        assert_eq!(parse_timestamp("2021-05-14T00:00:00"), None);
+        assert_eq!(parse_timestamp("2021-13-14T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("2021-05-14T+1:00:00Z"), None);
+    }
 }
@@ -0,0 +1,276 @@
+use crate::events;
+use crate::game::file::Status;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::mpsc::Sender,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A single entry in a session file, holding something read from the game's
+/// files at a time offset (in milliseconds) from the start of the session.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct SessionEntry {
+    pub milliseconds: u64,
+    #[serde(flatten)]
+    pub record: SessionRecord,
+}
+
+/// The content of a `SessionEntry`, either a status read from the status file
+/// or a raw line read from the journal.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionRecord {
+    Status(Status),
+    Journal(String),
+}
+
+/// A writer that records what is read from the game's files to a session file
+/// so that it can be replayed later.
+pub struct SessionRecorder {
+    session_file: File,
+    start: Instant,
+}
+
+impl SessionRecorder {
+    /// Returns a new instance that records to the given file, which is created
+    /// or else appended to so earlier sessions are kept. Panics if the file
+    /// cannot be opened.
+    pub fn create(session_file_path: PathBuf) -> Self {
+        let session_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(session_file_path)
+            .expect("Can't open session file");
+
+        Self {
+            session_file,
+            start: Instant::now(),
+        }
+    }
+
+    /// Records the given status.
+    pub fn record_status(&mut self, status: &Status) {
+        self.record(SessionRecord::Status(status.clone()));
+    }
+
+    /// Records the given raw journal line.
+    pub fn record_journal_line(&mut self, line: &str) {
+        self.record(SessionRecord::Journal(String::from(line.trim_end())));
+    }
+
+    /// Appends an entry for the given record, timed from the creation of the
+    /// recorder, to the session file. Panics if the file cannot be written.
+    fn record(&mut self, record: SessionRecord) {
+        let entry = SessionEntry {
+            milliseconds: self.start.elapsed().as_millis() as u64,
+            record,
+        };
+        let json = serde_json::to_string(&entry).expect("Can't serialize session entry");
+
+        writeln!(self.session_file, "{}", json).expect("Can't write session file");
+        self.session_file.flush().expect("Can't write session file");
+    }
+}
+
+/// The temporary journal file written by a replay, which is deleted when this
+/// is dropped.
+pub struct ReplayJournal {
+    path: PathBuf,
+}
+
+impl Drop for ReplayJournal {
+    fn drop(&mut self) {
+        if let Err(error) = fs::remove_file(&self.path) {
+            warn!("Can't delete replay journal {:?}: {}", self.path, error);
+        }
+    }
+}
+
+/// Replays the session in the given file on a new thread at the given speed
+/// multiplier. Each status is sent as a `StatusUpdate` event and each journal
+/// line is appended to a temporary journal file, announced up front with a
+/// `NewJournalFile` event, so events follow the same path as in the game. An
+/// `Exit` event is sent at the end of the session. Returns the journal, which
+/// must be kept until the events have been handled.
+pub fn replay(session_file_path: PathBuf, speed: f64, tx: &Sender<events::Event>) -> ReplayJournal {
+    let session_file = File::open(&session_file_path).expect("Can't open session file");
+    let entries = entries_from_buf_reader(&mut BufReader::new(session_file));
+    debug!("Replaying {} session entries", entries.len());
+
+    let journal_file_path =
+        env::temp_dir().join(format!("edxlc-replay-{}.log", std::process::id()));
+    let mut journal_file = File::create(&journal_file_path).expect("Can't create replay journal");
+
+    tx.send(events::Event::NewJournalFile(journal_file_path.clone()))
+        .expect("Can't send new journal file message for replay journal");
+
+    let tx = tx.clone();
+
+    thread::spawn(move || {
+        let start = Instant::now();
+
+        for entry in entries {
+            let offset = Duration::from_millis(entry.milliseconds).div_f64(speed);
+
+            if let Some(delay) = offset.checked_sub(start.elapsed()) {
+                thread::sleep(delay);
+            }
+
+            match entry.record {
+                SessionRecord::Status(status) => tx
+                    .send(events::Event::StatusUpdate(status))
+                    .expect("Could not send status update message"),
+                SessionRecord::Journal(line) => {
+                    writeln!(journal_file, "{}", line).expect("Can't write replay journal");
+                    journal_file.flush().expect("Can't write replay journal");
+                }
+            }
+        }
+
+        info!("Replay finished");
+        tx.send(events::Event::Exit)
+            .expect("Could not send exit message");
+    });
+
+    ReplayJournal {
+        path: journal_file_path,
+    }
+}
+
+/// Reads every line from the given reader and parses it as a session entry.
+fn entries_from_buf_reader<T>(reader: &mut BufReader<T>) -> Vec<SessionEntry>
+where
+    T: std::io::Read,
+{
+    reader
+        .lines()
+        .map(|line| line.expect("Can't read session file"))
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(&line).expect("Can't parse session entry JSON"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::file::LegalState;
+
+    #[test]
+    fn session_recorder_records_entries_that_can_be_read_back() {
+        let session_file_path =
+            env::temp_dir().join(format!("edxlc-session-test-{}.jsonl", std::process::id()));
+        let status = Status {
+            flags: 4,
+            flags2: 0,
+            legal_state: LegalState::Speeding,
+            ..Default::default()
+        };
+        let journal_line = r#"{ "event":"DockingGranted" }"#;
+
+        let mut recorder = SessionRecorder::create(session_file_path.clone());
+        recorder.record_journal_line(&format!("{}\r\n", journal_line));
+        recorder.record_status(&status);
+
+        let session_file = File::open(&session_file_path).unwrap();
+        let records: Vec<SessionRecord> =
+            entries_from_buf_reader(&mut BufReader::new(session_file))
+                .into_iter()
+                .map(|entry| entry.record)
+                .collect();
+        std::fs::remove_file(session_file_path).unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                SessionRecord::Journal(String::from(journal_line)),
+                SessionRecord::Status(status),
+            ]
+        );
+    }
+
+    #[test]
+    fn session_recorder_appends_to_existing_session_file() {
+        let session_file_path = env::temp_dir().join(format!(
+            "edxlc-session-append-test-{}.jsonl",
+            std::process::id()
+        ));
+
+        SessionRecorder::create(session_file_path.clone()).record_journal_line("first");
+        SessionRecorder::create(session_file_path.clone()).record_journal_line("second");
+
+        let session_file = File::open(&session_file_path).unwrap();
+        let records: Vec<SessionRecord> =
+            entries_from_buf_reader(&mut BufReader::new(session_file))
+                .into_iter()
+                .map(|entry| entry.record)
+                .collect();
+        std::fs::remove_file(session_file_path).unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                SessionRecord::Journal(String::from("first")),
+                SessionRecord::Journal(String::from("second")),
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_deletes_journal_when_dropped() {
+        let session_file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("session.jsonl");
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let replay_journal = replay(session_file_path, 100.0, &tx);
+        let events: Vec<events::Event> = rx
+            .iter()
+            .take_while(|event| *event != events::Event::Exit)
+            .collect();
+
+        assert_eq!(
+            events.first(),
+            Some(&events::Event::NewJournalFile(replay_journal.path.clone()))
+        );
+        assert!(replay_journal.path.exists());
+
+        let journal_file_path = replay_journal.path.clone();
+        drop(replay_journal);
+        assert!(!journal_file_path.exists());
+    }
+
+    #[test]
+    fn entries_from_buf_reader_parses_status_and_journal_entries() {
+        let session = r#"{"milliseconds":0,"status":{"Flags":4,"LegalState":"Speeding"}}
+
+{"milliseconds":1500,"journal":"{ \"event\":\"DockingGranted\" }"}
+"#
+        .as_bytes();
+
+        assert_eq!(
+            entries_from_buf_reader(&mut BufReader::new(session)),
+            vec![
+                SessionEntry {
+                    milliseconds: 0,
+                    record: SessionRecord::Status(Status {
+                        flags: 4,
+                        flags2: 0,
+                        legal_state: LegalState::Speeding,
+                        ..Default::default()
+                    }),
+                },
+                SessionEntry {
+                    milliseconds: 1500,
+                    record: SessionRecord::Journal(String::from(r#"{ "event":"DockingGranted" }"#)),
+                },
+            ]
+        );
+    }
+}
@@ -1,7 +1,15 @@
-use super::file::{journal::Event, LegalState, Status as FileStatus};
+mod expression;
+
+use super::file::{
+    journal::Event, GuiFocus as FileGuiFocus, LegalState, Pips, Status as FileStatus,
+};
+use super::ControlSet;
+use expression::{Expression, Names};
 use log::{info, warn};
+use serde::{de::Error, Deserialize, Deserializer};
+use std::time::{Duration, Instant, SystemTime};
 
-type StatusBitField = u64;
+type StatusBitField = u128;
 
 // See: https://elite-journal.readthedocs.io/en/latest/Status%20File/
 const LANDING_GEAR_DEPLOYED: StatusBitField = 1 << 2;
@@ -10,16 +18,119 @@ const HARDPOINTS_DEPLOYED: StatusBitField = 1 << 6;
 const EXTERNAL_LIGHTS_ON: StatusBitField = 1 << 8;
 const CARGO_SCOOP_DEPLOYED: StatusBitField = 1 << 9;
 const SILENT_RUNNING: StatusBitField = 1 << 10;
+const FUEL_SCOOPING: StatusBitField = 1 << 11;
+const SRV_HANDBRAKE: StatusBitField = 1 << 12;
+const SRV_TURRET_VIEW: StatusBitField = 1 << 13;
+const SRV_TURRET_RETRACTED: StatusBitField = 1 << 14;
+const SRV_DRIVE_ASSIST: StatusBitField = 1 << 15;
 const MASS_LOCKED: StatusBitField = 1 << 16;
 const FRAME_SHIFT_DRIVE_CHARGING: StatusBitField = 1 << 17;
 const FRAME_SHIFT_DRIVE_COOLDOWN: StatusBitField = 1 << 18;
 const OVERHEATING: StatusBitField = 1 << 20;
+const IN_DANGER: StatusBitField = 1 << 22;
+const BEING_INTERDICTED: StatusBitField = 1 << 23;
+const IN_MAIN_SHIP: StatusBitField = 1 << 24;
+const IN_FIGHTER: StatusBitField = 1 << 25;
+const IN_SRV: StatusBitField = 1 << 26;
+const HUD_IN_ANALYSIS_MODE: StatusBitField = 1 << 27;
+const SRV_HIGH_BEAM: StatusBitField = 1 << 31;
 const NIGHT_VISION_ON: StatusBitField = 1 << 28;
 
+// The second (Odyssey) flag field is packed above the first.
+const FLAGS2_SHIFT: u32 = 32;
+const ON_FOOT: StatusBitField = 1 << FLAGS2_SHIFT;
+const IN_TAXI: StatusBitField = 1 << (FLAGS2_SHIFT + 1);
+const IN_MULTICREW: StatusBitField = 1 << (FLAGS2_SHIFT + 2);
+const ON_FOOT_IN_STATION: StatusBitField = 1 << (FLAGS2_SHIFT + 3);
+const LOW_OXYGEN: StatusBitField = 1 << (FLAGS2_SHIFT + 6);
+const LOW_HEALTH: StatusBitField = 1 << (FLAGS2_SHIFT + 7);
+const COLD: StatusBitField = 1 << (FLAGS2_SHIFT + 8);
+const HOT: StatusBitField = 1 << (FLAGS2_SHIFT + 9);
+const VERY_COLD: StatusBitField = 1 << (FLAGS2_SHIFT + 10);
+const VERY_HOT: StatusBitField = 1 << (FLAGS2_SHIFT + 11);
+const GLIDE_MODE: StatusBitField = 1 << (FLAGS2_SHIFT + 12);
+const ON_FOOT_SOCIAL_SPACE: StatusBitField = 1 << (FLAGS2_SHIFT + 14);
+const BREATHABLE_ATMOSPHERE: StatusBitField = 1 << (FLAGS2_SHIFT + 16);
+
 // These statuses are derived from sources other than the flag fields (e.g.
-// legal status and journal events) so we pack them into the unused high bits.
-const DOCKING: StatusBitField = 1 << (32 + 16);
-const SPEEDING: StatusBitField = 1 << (32 + 17);
+// legal status and journal events) so we pack them above both flag fields,
+// leaving the unused bits of each free for flags the game may add.
+const DERIVED_SHIFT: u32 = 64;
+const DOCKING: StatusBitField = 1 << DERIVED_SHIFT;
+const SPEEDING: StatusBitField = 1 << (DERIVED_SHIFT + 1);
+const PILOTING_FIGHTER: StatusBitField = 1 << (DERIVED_SHIFT + 2);
+const CREWING: StatusBitField = 1 << (DERIVED_SHIFT + 3);
+const FUEL_SCOOPED: StatusBitField = 1 << (DERIVED_SHIFT + 4);
+const SHIELDS_DOWN: StatusBitField = 1 << (DERIVED_SHIFT + 5);
+const DANGER_ALERT: StatusBitField = 1 << (DERIVED_SHIFT + 6);
+const SHIELDS_RESTORED: StatusBitField = 1 << (DERIVED_SHIFT + 7);
+const JUMPED: StatusBitField = 1 << (DERIVED_SHIFT + 8);
+const SCAN_COMPLETE: StatusBitField = 1 << (DERIVED_SHIFT + 9);
+const TIMER_RUNNING: StatusBitField = 1 << (DERIVED_SHIFT + 10);
+const TIMER_EXPIRING: StatusBitField = 1 << (DERIVED_SHIFT + 11);
+const HEAT_SINK_LAUNCHED: StatusBitField = 1 << (DERIVED_SHIFT + 12);
+
+/// The fraction of the main tank below which fuel is low if not configured.
+pub const DEFAULT_LOW_FUEL_FRACTION: f64 = 0.25;
+
+/// How long a danger alert lasts, in seconds, if not configured.
+pub const DEFAULT_ALERT_SECONDS: f64 = 5.0;
+
+/// How long before a countdown timer expires, in seconds, that it's about to
+/// expire if not configured.
+pub const DEFAULT_TIMER_WARNING_SECONDS: f64 = 60.0;
+
+/// Settings that tune when statuses derived by the ship are raised.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShipSettings {
+    /// Fuel is low below this fraction of the main tank.
+    pub low_fuel_fraction: f64,
+    /// How long a danger alert lasts.
+    pub alert_duration: Duration,
+    /// How long before a countdown timer expires that it's about to expire.
+    pub timer_warning: Duration,
+}
+
+impl Default for ShipSettings {
+    fn default() -> Self {
+        Self {
+            low_fuel_fraction: DEFAULT_LOW_FUEL_FRACTION,
+            alert_duration: Duration::from_secs_f64(DEFAULT_ALERT_SECONDS),
+            timer_warning: Duration::from_secs_f64(DEFAULT_TIMER_WARNING_SECONDS),
+        }
+    }
+}
+
+// How long after docking is granted that the request times out.
+const DOCKING_TIMEOUT: Duration = Duration::from_secs(600);
+
+// How long the other statuses raised briefly by journal events last.
+const TIMED_STATUS_DURATION: Duration = Duration::from_secs(3);
+
+// How long a message about a journal event is shown for.
+const MESSAGE_DURATION: Duration = Duration::from_secs(10);
+
+// The names of text values, in addition to the numeric values, that can be
+// shown on the MFD.
+const SYSTEM_TEXT_NAME: &str = "system";
+const TARGET_TEXT_NAME: &str = "target";
+
+// The statuses derived from journal events, which must survive status updates.
+const JOURNAL_STATUSES: StatusBitField = DOCKING
+    | PILOTING_FIGHTER
+    | CREWING
+    | FUEL_SCOOPED
+    | SHIELDS_DOWN
+    | DANGER_ALERT
+    | SHIELDS_RESTORED
+    | JUMPED
+    | SCAN_COMPLETE
+    | TIMER_RUNNING
+    | TIMER_EXPIRING
+    | HEAT_SINK_LAUNCHED;
+
+// The statuses that raise the high-priority danger alert.
+const DANGER_STATUSES: StatusBitField = DANGER_ALERT | BEING_INTERDICTED | IN_DANGER;
 
 const STATUS_FILTER: StatusBitField = LANDING_GEAR_DEPLOYED
     | CARGO_SCOOP_DEPLOYED
@@ -28,25 +139,202 @@ const STATUS_FILTER: StatusBitField = LANDING_GEAR_DEPLOYED
     | MASS_LOCKED
     | FRAME_SHIFT_DRIVE_COOLDOWN
     | OVERHEATING
+    | IN_DANGER
+    | BEING_INTERDICTED
     | SILENT_RUNNING
+    | FUEL_SCOOPING
     | HARDPOINTS_DEPLOYED
+    | HUD_IN_ANALYSIS_MODE
     | SUPERCRUISE
     | SPEEDING
-    | NIGHT_VISION_ON;
+    | NIGHT_VISION_ON
+    | SRV_HANDBRAKE
+    | SRV_TURRET_VIEW
+    | SRV_TURRET_RETRACTED
+    | SRV_DRIVE_ASSIST
+    | IN_SRV
+    | SRV_HIGH_BEAM
+    | IN_MAIN_SHIP
+    | IN_FIGHTER
+    | ON_FOOT
+    | IN_TAXI
+    | IN_MULTICREW
+    | ON_FOOT_IN_STATION
+    | LOW_OXYGEN
+    | LOW_HEALTH
+    | COLD
+    | HOT
+    | VERY_COLD
+    | VERY_HOT
+    | GLIDE_MODE
+    | ON_FOOT_SOCIAL_SPACE
+    | BREATHABLE_ATMOSPHERE;
+
+// The names used to refer to statuses in configured rules.
+const STATUS_NAMES: [(&str, StatusBitField); 49] = [
+    ("landing-gear-deployed", LANDING_GEAR_DEPLOYED),
+    ("supercruise", SUPERCRUISE),
+    ("hardpoints-deployed", HARDPOINTS_DEPLOYED),
+    ("external-lights-on", EXTERNAL_LIGHTS_ON),
+    ("cargo-scoop-deployed", CARGO_SCOOP_DEPLOYED),
+    ("silent-running", SILENT_RUNNING),
+    ("fuel-scooping", FUEL_SCOOPING),
+    ("srv-handbrake", SRV_HANDBRAKE),
+    ("srv-turret-view", SRV_TURRET_VIEW),
+    ("srv-turret-retracted", SRV_TURRET_RETRACTED),
+    ("srv-drive-assist", SRV_DRIVE_ASSIST),
+    ("mass-locked", MASS_LOCKED),
+    ("frame-shift-drive-charging", FRAME_SHIFT_DRIVE_CHARGING),
+    ("frame-shift-drive-cooldown", FRAME_SHIFT_DRIVE_COOLDOWN),
+    ("overheating", OVERHEATING),
+    ("in-danger", IN_DANGER),
+    ("being-interdicted", BEING_INTERDICTED),
+    ("in-main-ship", IN_MAIN_SHIP),
+    ("in-fighter", IN_FIGHTER),
+    ("in-srv", IN_SRV),
+    ("hud-in-analysis-mode", HUD_IN_ANALYSIS_MODE),
+    ("night-vision-on", NIGHT_VISION_ON),
+    ("srv-high-beam", SRV_HIGH_BEAM),
+    ("on-foot", ON_FOOT),
+    ("in-taxi", IN_TAXI),
+    ("in-multicrew", IN_MULTICREW),
+    ("on-foot-in-station", ON_FOOT_IN_STATION),
+    ("low-oxygen", LOW_OXYGEN),
+    ("low-health", LOW_HEALTH),
+    ("cold", COLD),
+    ("hot", HOT),
+    ("very-cold", VERY_COLD),
+    ("very-hot", VERY_HOT),
+    ("glide-mode", GLIDE_MODE),
+    ("on-foot-social-space", ON_FOOT_SOCIAL_SPACE),
+    ("breathable-atmosphere", BREATHABLE_ATMOSPHERE),
+    ("docking", DOCKING),
+    ("speeding", SPEEDING),
+    ("piloting-fighter", PILOTING_FIGHTER),
+    ("crewing", CREWING),
+    ("fuel-scooped", FUEL_SCOOPED),
+    ("shields-down", SHIELDS_DOWN),
+    ("danger-alert", DANGER_ALERT),
+    ("shields-restored", SHIELDS_RESTORED),
+    ("jumped", JUMPED),
+    ("scan-complete", SCAN_COMPLETE),
+    ("timer-running", TIMER_RUNNING),
+    ("timer-expiring", TIMER_EXPIRING),
+    ("heat-sink-launched", HEAT_SINK_LAUNCHED),
+];
+
+/// A countdown started by a journal event, e.g. to dock before the docking
+/// request times out.
+///
+/// There's no frame shift drive cooldown timer: `FSDJump` doesn't give the
+/// cooldown's length, which varies with the drive, and the status file's
+/// `FRAME_SHIFT_DRIVE_COOLDOWN` flag already blocks the FSD lights until the
+/// cooldown ends.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Timer {
+    CarrierJump,
+    Docking,
+}
+
+// The names of the text values for the time remaining on each timer.
+const TIMER_NAMES: [(&str, Timer); 2] = [
+    ("carrier-jump-timer", Timer::CarrierJump),
+    ("docking-timer", Timer::Docking),
+];
+
+/// A numeric value read from the status file (or derived from it) that can be
+/// compared in configured rules.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    Altitude,
+    Cargo,
+    EnginesPips,
+    FireGroup,
+    FireGroups,
+    Fuel,
+    FuelMain,
+    FuelReservoir,
+    GuiFocus,
+    Heading,
+    Health,
+    Oxygen,
+    SystemsPips,
+    WeaponsPips,
+}
+
+// The names used to refer to values in configured rules.
+const VALUE_NAMES: [(&str, Value); 14] = [
+    ("altitude", Value::Altitude),
+    ("cargo", Value::Cargo),
+    ("engines-pips", Value::EnginesPips),
+    ("fire-group", Value::FireGroup),
+    ("fire-groups", Value::FireGroups),
+    ("fuel", Value::Fuel),
+    ("fuel-main", Value::FuelMain),
+    ("fuel-reservoir", Value::FuelReservoir),
+    ("gui-focus", Value::GuiFocus),
+    ("heading", Value::Heading),
+    ("health", Value::Health),
+    ("oxygen", Value::Oxygen),
+    ("systems-pips", Value::SystemsPips),
+    ("weapons-pips", Value::WeaponsPips),
+];
+
+// Names for the numbers that values can be compared with, which are the focus
+// values for `gui-focus`.
+const CONSTANT_NAMES: [(&str, f64); 12] = [
+    ("no-focus", 0.0),
+    ("internal-panel", 1.0),
+    ("external-panel", 2.0),
+    ("comms-panel", 3.0),
+    ("role-panel", 4.0),
+    ("station-services", 5.0),
+    ("galaxy-map", 6.0),
+    ("system-map", 7.0),
+    ("orrery", 8.0),
+    ("fss", 9.0),
+    ("saa", 10.0),
+    ("codex", 11.0),
+];
+
+const NAMES: Names = Names {
+    statuses: &STATUS_NAMES,
+    values: &VALUE_NAMES,
+    constants: &CONSTANT_NAMES,
+};
 
 /// An attribute of a `Ship` that can be associated with a value.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
 pub enum Attribute {
     Boost,
     CargoScoop,
+    EnginesPower,
     ExternalLights,
+    FireGroups,
     FrameShiftDrive,
+    FuelScoop,
+    Glide,
     Hardpoints,
+    Health,
     HeatSink,
+    HudMode,
     LandingGear,
     NightVision,
+    Oxygen,
+    PowerDistribution,
     SilentRunning,
+    SrvDriveAssist,
+    SrvHandbrake,
+    SrvShip,
+    SrvTurret,
+    SuitEquipment,
+    SystemsPower,
+    Temperature,
     Throttle,
+    Timers,
+    UiNavigation,
+    WeaponsPower,
 }
 
 /// An association of a `Attribute` to a `StatusLevel` value for a `Ship`.
@@ -56,7 +344,8 @@ pub struct Status {
 }
 
 /// A status value that can associated to an `Attibute` through a `Status`
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, PartialOrd)]
+#[serde(rename_all = "kebab-case")]
 pub enum StatusLevel {
     Inactive,
     Active,
@@ -65,10 +354,61 @@ pub enum StatusLevel {
 }
 
 /// A condition that can be used to specify a `StatusLevel` through a
-/// `ConditionStatusLevelMapping`.
+/// `ConditionStatusLevelMapping`. Configured conditions list status names or
+/// give an expression over them.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
 enum Condition {
-    Any(StatusBitField),
-    All(StatusBitField),
+    Any(#[serde(deserialize_with = "status_flags_from_names")] StatusBitField),
+    All(#[serde(deserialize_with = "status_flags_from_names")] StatusBitField),
+    None(#[serde(deserialize_with = "status_flags_from_names")] StatusBitField),
+    When(#[serde(deserialize_with = "expression_from_str")] Expression),
+    // The main tank is below the configured low fuel fraction.
+    #[serde(skip_deserializing)]
+    LowFuel,
+}
+
+/// Deserializes a list of status names into a bit-field, failing on any name
+/// that isn't recognised.
+fn status_flags_from_names<'de, D>(deserializer: D) -> Result<StatusBitField, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let names = Vec::<String>::deserialize(deserializer)?;
+    let mut flags = 0;
+
+    for name in names {
+        match STATUS_NAMES
+            .iter()
+            .find(|(status_name, _)| *status_name == name)
+        {
+            Some((_, flag)) => flags |= flag,
+            None => return Err(D::Error::custom(format!("unknown status `{}`", name))),
+        }
+    }
+
+    Ok(flags)
+}
+
+/// Deserializes an expression over status names, failing with the column of
+/// any error.
+fn expression_from_str<'de, D>(deserializer: D) -> Result<Expression, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let source = String::deserialize(deserializer)?;
+    Expression::parse(&source, &NAMES)
+        .map_err(|error| D::Error::custom(format!("invalid expression `{}`: {}", source, error)))
+}
+
+/// A configured rule that sets the `StatusLevel` of an `Attribute` when the
+/// `Condition` applies. Rules for an attribute replace its built-in rules.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Rule {
+    attribute: Attribute,
+    #[serde(flatten)]
+    condition: Condition,
+    level: StatusLevel,
 }
 
 /// A mapping that defines the `Condition` that indicates a `StatusLevel`
@@ -110,11 +450,37 @@ impl AttributeStatusLevelMappings {
 pub enum GlobalStatus {
     Normal,
     HardpointsDeployed,
+    HardpointsDeployedAnalysis,
     NightVisionOn,
+    OnFoot,
+    InSrv,
+    InFighter,
+    Multicrew,
+    GalaxyMap,
+    SystemMap,
+    FullSpectrumScanner,
+    SurfaceAreaAnalysis,
+    StationServices,
+    CommsPanel,
 }
 
 pub struct Ship {
     status_flags: StatusBitField,
+    file_status: FileStatus,
+    fuel_capacity: Option<f64>,
+    // The number of fire groups seen since the loadout last changed.
+    fire_groups: u8,
+    // The number of other players in the crew of the ship.
+    crew_members: u8,
+    settings: ShipSettings,
+    // Statuses raised by journal events that last until the paired time.
+    timed_status_flags: Vec<(StatusBitField, Instant)>,
+    star_system: Option<String>,
+    target: Option<String>,
+    // A message about a recent journal event shown until the paired time.
+    message: Option<(String, Instant)>,
+    // Countdowns started by journal events that expire at the paired time.
+    timers: Vec<(Timer, Instant)>,
     attribute_status_level_mappings: Vec<AttributeStatusLevelMappings>,
 }
 
@@ -123,6 +489,16 @@ impl Ship {
     pub fn new() -> Self {
         Self {
             status_flags: 0,
+            file_status: FileStatus::default(),
+            fuel_capacity: None,
+            fire_groups: 0,
+            crew_members: 0,
+            settings: ShipSettings::default(),
+            timed_status_flags: vec![],
+            star_system: None,
+            target: None,
+            message: None,
+            timers: vec![],
             attribute_status_level_mappings: vec![
                 AttributeStatusLevelMappings::new(
                     Attribute::CargoScoop,
@@ -134,7 +510,7 @@ impl Ship {
                 AttributeStatusLevelMappings::new(
                     Attribute::ExternalLights,
                     vec![ConditionStatusLevelMapping::new(
-                        Condition::All(EXTERNAL_LIGHTS_ON),
+                        Condition::Any(EXTERNAL_LIGHTS_ON | SRV_HIGH_BEAM),
                         StatusLevel::Active,
                     )],
                 ),
@@ -145,6 +521,11 @@ impl Ship {
                             Condition::All(FRAME_SHIFT_DRIVE_CHARGING | OVERHEATING),
                             StatusLevel::Alert,
                         ),
+                        // Flashes briefly after a jump, before the cooldown.
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(JUMPED),
+                            StatusLevel::Alert,
+                        ),
                         // Supercruise is higher precendence than normal
                         // flight, specifically for blocking states like
                         // hardpoints deployed.
@@ -168,6 +549,92 @@ impl Ship {
                         ),
                     ],
                 ),
+                // Power can't be increased to a system at the maximum of four
+                // pips and is active when above the balanced two pips.
+                AttributeStatusLevelMappings::new(
+                    Attribute::EnginesPower,
+                    Self::power_mappings("engines-pips"),
+                ),
+                // Shields are powered by systems so it flashes briefly when
+                // they're restored.
+                AttributeStatusLevelMappings::new(
+                    Attribute::SystemsPower,
+                    vec![ConditionStatusLevelMapping::new(
+                        Condition::All(SHIELDS_RESTORED),
+                        StatusLevel::Alert,
+                    )]
+                    .into_iter()
+                    .chain(Self::power_mappings("systems-pips"))
+                    .collect(),
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::WeaponsPower,
+                    Self::power_mappings("weapons-pips"),
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::PowerDistribution,
+                    vec![ConditionStatusLevelMapping::new(
+                        Self::built_in_expression(
+                            "systems-pips != 2 or engines-pips != 2 or weapons-pips != 2",
+                        ),
+                        StatusLevel::Active,
+                    )],
+                ),
+                // The game doesn't say how many fire groups there are so
+                // there's more than one once a later group has been selected.
+                AttributeStatusLevelMappings::new(
+                    Attribute::FireGroups,
+                    vec![ConditionStatusLevelMapping::new(
+                        Self::built_in_expression("fire-groups > 1"),
+                        StatusLevel::Active,
+                    )],
+                ),
+                // Scanning is done in analysis mode so it flashes briefly when
+                // a scan completes.
+                AttributeStatusLevelMappings::new(
+                    Attribute::HudMode,
+                    vec![
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(SCAN_COMPLETE),
+                            StatusLevel::Alert,
+                        ),
+                        ConditionStatusLevelMapping::new(
+                            Condition::Any(HUD_IN_ANALYSIS_MODE),
+                            StatusLevel::Active,
+                        ),
+                    ],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::UiNavigation,
+                    vec![ConditionStatusLevelMapping::new(
+                        Self::built_in_expression("gui-focus != no-focus"),
+                        StatusLevel::Active,
+                    )],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::Timers,
+                    vec![
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(TIMER_EXPIRING),
+                            StatusLevel::Alert,
+                        ),
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(TIMER_RUNNING),
+                            StatusLevel::Active,
+                        ),
+                    ],
+                ),
+                // Scooping resolves low fuel so takes precedence.
+                AttributeStatusLevelMappings::new(
+                    Attribute::FuelScoop,
+                    vec![
+                        ConditionStatusLevelMapping::new(
+                            Condition::Any(FUEL_SCOOPING | FUEL_SCOOPED),
+                            StatusLevel::Active,
+                        ),
+                        ConditionStatusLevelMapping::new(Condition::LowFuel, StatusLevel::Alert),
+                    ],
+                ),
                 AttributeStatusLevelMappings::new(
                     Attribute::LandingGear,
                     vec![
@@ -184,7 +651,7 @@ impl Ship {
                 AttributeStatusLevelMappings::new(
                     Attribute::HeatSink,
                     vec![ConditionStatusLevelMapping::new(
-                        Condition::All(OVERHEATING),
+                        Condition::Any(OVERHEATING | HEAT_SINK_LAUNCHED),
                         StatusLevel::Alert,
                     )],
                 ),
@@ -210,22 +677,123 @@ impl Ship {
                 ),
                 AttributeStatusLevelMappings::new(
                     Attribute::Boost,
+                    vec![
+                        ConditionStatusLevelMapping::new(
+                            Condition::Any(DANGER_STATUSES),
+                            StatusLevel::Alert,
+                        ),
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(LANDING_GEAR_DEPLOYED),
+                            StatusLevel::Blocked,
+                        ),
+                    ],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::Throttle,
+                    vec![
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(SPEEDING),
+                            StatusLevel::Alert,
+                        ),
+                        ConditionStatusLevelMapping::new(
+                            Condition::Any(DANGER_STATUSES),
+                            StatusLevel::Alert,
+                        ),
+                    ],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::NightVision,
                     vec![ConditionStatusLevelMapping::new(
-                        Condition::All(LANDING_GEAR_DEPLOYED),
-                        StatusLevel::Blocked,
+                        Condition::All(NIGHT_VISION_ON),
+                        StatusLevel::Active,
                     )],
                 ),
                 AttributeStatusLevelMappings::new(
-                    Attribute::Throttle,
+                    Attribute::Health,
                     vec![ConditionStatusLevelMapping::new(
-                        Condition::All(SPEEDING),
+                        Condition::All(LOW_HEALTH),
                         StatusLevel::Alert,
                     )],
                 ),
                 AttributeStatusLevelMappings::new(
-                    Attribute::NightVision,
+                    Attribute::Oxygen,
+                    vec![
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(LOW_OXYGEN),
+                            StatusLevel::Alert,
+                        ),
+                        // The helmet is not needed so oxygen is not being
+                        // used.
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(BREATHABLE_ATMOSPHERE),
+                            StatusLevel::Inactive,
+                        ),
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(ON_FOOT),
+                            StatusLevel::Active,
+                        ),
+                    ],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::Temperature,
+                    vec![
+                        ConditionStatusLevelMapping::new(
+                            Condition::Any(VERY_COLD | VERY_HOT),
+                            StatusLevel::Alert,
+                        ),
+                        ConditionStatusLevelMapping::new(
+                            Condition::Any(COLD | HOT),
+                            StatusLevel::Active,
+                        ),
+                    ],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::SrvDriveAssist,
                     vec![ConditionStatusLevelMapping::new(
-                        Condition::All(NIGHT_VISION_ON),
+                        Condition::All(SRV_DRIVE_ASSIST),
+                        StatusLevel::Active,
+                    )],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::SrvHandbrake,
+                    vec![ConditionStatusLevelMapping::new(
+                        Condition::All(SRV_HANDBRAKE),
+                        StatusLevel::Active,
+                    )],
+                ),
+                // The turret is retracted when the SRV is close to the ship,
+                // so the ship can be boarded rather than recalled.
+                AttributeStatusLevelMappings::new(
+                    Attribute::SrvShip,
+                    vec![ConditionStatusLevelMapping::new(
+                        Condition::All(SRV_TURRET_RETRACTED),
+                        StatusLevel::Active,
+                    )],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::SrvTurret,
+                    vec![
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(SRV_TURRET_RETRACTED),
+                            StatusLevel::Blocked,
+                        ),
+                        ConditionStatusLevelMapping::new(
+                            Condition::All(SRV_TURRET_VIEW),
+                            StatusLevel::Active,
+                        ),
+                    ],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::SuitEquipment,
+                    vec![ConditionStatusLevelMapping::new(
+                        Condition::Any(ON_FOOT_IN_STATION | ON_FOOT_SOCIAL_SPACE),
+                        StatusLevel::Blocked,
+                    )],
+                ),
+                AttributeStatusLevelMappings::new(
+                    Attribute::Glide,
+                    vec![ConditionStatusLevelMapping::new(
+                        Condition::All(GLIDE_MODE),
                         StatusLevel::Active,
                     )],
                 ),
@@ -238,19 +806,295 @@ impl Ship {
         match event {
             Event::Docked | Event::DockingCancelled | Event::DockingTimeout => {
                 info!("Docking terminated");
-                self.status_flags &= !DOCKING
+                self.status_flags &= !DOCKING;
+                self.stop_timer(Timer::Docking)
             }
-            Event::DockingGranted => {
+            Event::DockingGranted {
+                landing_pad,
+                timestamp,
+            } => {
                 info!("Docking commenced");
-                self.status_flags |= DOCKING
+                self.status_flags |= DOCKING;
+                let granted = timestamp.unwrap_or_else(SystemTime::now);
+                self.start_timer(Timer::Docking, granted + DOCKING_TIMEOUT);
+                self.show_message(match landing_pad {
+                    Some(landing_pad) => format!("Docking: pad {}", landing_pad),
+                    None => String::from("Docking granted"),
+                })
+            }
+            Event::LaunchFighter { player_controlled } => {
+                if player_controlled {
+                    info!("Piloting fighter");
+                    self.status_flags |= PILOTING_FIGHTER
+                }
+            }
+            Event::DockFighter => {
+                info!("Fighter docked");
+                self.status_flags &= !PILOTING_FIGHTER
+            }
+            // The session on the player's own ship lasts until the last crew
+            // member quits.
+            Event::CrewMemberJoins => {
+                info!("Crew member joined");
+                self.crew_members = self.crew_members.saturating_add(1);
+                self.status_flags |= CREWING
+            }
+            Event::CrewMemberQuits => {
+                info!("Crew member quit");
+                self.crew_members = self.crew_members.saturating_sub(1);
+                if self.crew_members == 0 {
+                    self.status_flags &= !CREWING
+                }
+            }
+            Event::JoinACrew => {
+                info!("Multicrew session commenced");
+                self.status_flags |= CREWING
+            }
+            Event::QuitACrew => {
+                info!("Multicrew session terminated");
+                self.crew_members = 0;
+                self.status_flags &= !CREWING
+            }
+            Event::FuelScoop => {
+                info!("Fuel scooped");
+                self.raise_status_for(FUEL_SCOOPED, TIMED_STATUS_DURATION)
+            }
+            Event::Loadout { fuel_capacity } => {
+                info!("Fuel capacity {:?}", fuel_capacity);
+                self.fuel_capacity = fuel_capacity.map(|fuel_capacity| fuel_capacity.main);
+                self.fire_groups = 0
+            }
+            // The player is the interdictor so there's no danger to alert.
+            Event::Interdiction => info!("Interdicted another ship"),
+            Event::Interdicted | Event::UnderAttack | Event::HullDamage | Event::HeatWarning => {
+                info!("Danger alert raised");
+                if let Some(message) = match event {
+                    Event::Interdicted => Some("Interdicted"),
+                    Event::UnderAttack => Some("Under attack"),
+                    Event::HeatWarning => Some("Heat warning"),
+                    _ => None,
+                } {
+                    self.show_message(String::from(message));
+                }
+                self.raise_status_for(DANGER_ALERT, self.settings.alert_duration)
+            }
+            Event::EscapeInterdiction => {
+                info!("Danger alert cleared");
+                self.clear_timed_status(DANGER_ALERT)
+            }
+            Event::ShieldState { shields_up } => {
+                if shields_up {
+                    info!("Shields restored");
+                    self.status_flags &= !SHIELDS_DOWN;
+                    self.raise_status_for(SHIELDS_RESTORED, TIMED_STATUS_DURATION)
+                } else {
+                    info!("Shields down");
+                    self.status_flags |= SHIELDS_DOWN;
+                    self.show_message(String::from("Shields down"));
+                    self.clear_timed_status(SHIELDS_RESTORED);
+                    self.raise_status_for(DANGER_ALERT, self.settings.alert_duration)
+                }
+            }
+            Event::FsdJump { star_system } => {
+                info!("Jumped into system {}", star_system);
+                self.star_system = Some(star_system);
+                self.raise_status_for(JUMPED, TIMED_STATUS_DURATION)
+            }
+            Event::Location { star_system } => {
+                info!("Located in system {}", star_system);
+                self.star_system = Some(star_system)
+            }
+            Event::FsdTarget { name } => {
+                info!("Targeted system {}", name);
+                self.target = Some(name)
+            }
+            Event::CarrierJumpRequest { departure_time } => match departure_time {
+                Some(departure_time) => {
+                    info!("Carrier jump requested");
+                    self.start_timer(Timer::CarrierJump, departure_time)
+                }
+                None => warn!("Carrier jump requested without a departure time"),
+            },
+            Event::CarrierJump | Event::CarrierJumpCancelled => {
+                info!("Carrier jump terminated");
+                self.stop_timer(Timer::CarrierJump)
+            }
+            Event::HeatSinkLaunched => {
+                info!("Heat sink launched");
+                self.raise_status_for(HEAT_SINK_LAUNCHED, TIMED_STATUS_DURATION)
+            }
+            Event::Scan | Event::SaaScanComplete => {
+                info!("Scan complete");
+                self.raise_status_for(SCAN_COMPLETE, TIMED_STATUS_DURATION)
             }
             Event::Other => warn!("Can't apply `Event::Other` journal event"),
         };
     }
 
+    /// Raises the given status for the given duration from now, extending it
+    /// if already raised. There's no journal event to end the status so it's
+    /// expired by `update_time`.
+    fn raise_status_for(&mut self, status_flag: StatusBitField, duration: Duration) {
+        self.clear_timed_status(status_flag);
+        self.status_flags |= status_flag;
+        self.timed_status_flags
+            .push((status_flag, Instant::now() + duration));
+    }
+
+    fn clear_timed_status(&mut self, status_flag: StatusBitField) {
+        self.status_flags &= !status_flag;
+        self.timed_status_flags
+            .retain(|(timed_status_flag, _)| *timed_status_flag != status_flag);
+    }
+
+    /// Starts the given timer counting down to the given time, restarting it if
+    /// already started. Timers for times already passed, e.g. from old journal
+    /// events, aren't started.
+    fn start_timer(&mut self, timer: Timer, expiry: SystemTime) {
+        self.timers
+            .retain(|(started_timer, _)| *started_timer != timer);
+
+        match expiry.duration_since(SystemTime::now()) {
+            Ok(remaining) => {
+                info!("Timer {:?} started with {:?} remaining", timer, remaining);
+                let now = Instant::now();
+                self.timers.push((timer, now + remaining));
+                self.update_timer_statuses(now);
+            }
+            Err(_) => info!("Timer {:?} not started as already expired", timer),
+        }
+    }
+
+    fn stop_timer(&mut self, timer: Timer) {
+        self.timers
+            .retain(|(started_timer, _)| *started_timer != timer);
+        self.update_timer_statuses(Instant::now());
+    }
+
+    /// Expires timers and sets the timer statuses as of the given time,
+    /// returning true if the statuses have changed.
+    fn update_timer_statuses(&mut self, now: Instant) -> bool {
+        self.timers.retain(|(_, expiry)| now < *expiry);
+
+        let mut timer_status_flags = 0;
+        if !self.timers.is_empty() {
+            timer_status_flags |= TIMER_RUNNING;
+        }
+        if self
+            .timers
+            .iter()
+            .any(|(_, expiry)| *expiry - now <= self.settings.timer_warning)
+        {
+            timer_status_flags |= TIMER_EXPIRING;
+        }
+
+        let previous_status_flags = self.status_flags;
+        self.status_flags =
+            self.status_flags & !(TIMER_RUNNING | TIMER_EXPIRING) | timer_status_flags;
+        self.status_flags != previous_status_flags
+    }
+
+    /// Returns the time remaining on the given timer as of the given time, in
+    /// minutes and seconds (and hours if needed), if started.
+    fn timer_text(&self, timer: Timer, now: Instant) -> Option<String> {
+        let (_, expiry) = self
+            .timers
+            .iter()
+            .find(|(started_timer, _)| *started_timer == timer)?;
+        // Round up so the timer only shows zero once expired.
+        let remaining = expiry.saturating_duration_since(now);
+        let seconds = (remaining.as_millis() as u64).div_ceil(1000);
+
+        Some(if seconds >= 3600 {
+            format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        } else {
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        })
+    }
+
+    /// Shows the given message about a journal event for a while.
+    fn show_message(&mut self, message: String) {
+        self.message = Some((message, Instant::now() + MESSAGE_DURATION));
+    }
+
+    /// Returns the message about a recent journal event, if still shown.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref().map(|(message, _)| message.as_str())
+    }
+
+    /// Returns the named text for display if currently known. Numeric values
+    /// are rounded to whole numbers.
+    pub fn text(&self, name: &str) -> Option<String> {
+        match name {
+            SYSTEM_TEXT_NAME => self.star_system.clone(),
+            TARGET_TEXT_NAME => self.target.clone(),
+            name => {
+                if let Some((_, timer)) = TIMER_NAMES
+                    .iter()
+                    .find(|(timer_name, _)| *timer_name == name)
+                {
+                    return self.timer_text(*timer, Instant::now());
+                }
+                let (_, value) = VALUE_NAMES
+                    .iter()
+                    .find(|(value_name, _)| *value_name == name)?;
+                self.value(*value).map(|value| format!("{:.0}", value))
+            }
+        }
+    }
+
+    /// Returns true if the ship has text for display with the given name.
+    pub fn is_text_name(name: &str) -> bool {
+        name == SYSTEM_TEXT_NAME
+            || name == TARGET_TEXT_NAME
+            || TIMER_NAMES
+                .iter()
+                .any(|(timer_name, _)| *timer_name == name)
+            || VALUE_NAMES
+                .iter()
+                .any(|(value_name, _)| *value_name == name)
+    }
+
+    /// Expires statuses, messages and timers that only last for a time as of
+    /// the given time, returning true if any have expired or a timer is about
+    /// to expire.
+    pub fn update_time(&mut self, now: Instant) -> bool {
+        let message_expired = matches!(&self.message, Some((_, expiry)) if now >= *expiry);
+        if message_expired {
+            self.message = None;
+        }
+
+        let mut expired_status_flags = 0;
+
+        self.timed_status_flags.retain(|(status_flag, expiry)| {
+            let expired = now >= *expiry;
+            if expired {
+                expired_status_flags |= status_flag;
+            }
+            !expired
+        });
+
+        if expired_status_flags != 0 {
+            info!("Timed statuses expired {:#x}", expired_status_flags);
+            self.status_flags &= !expired_status_flags;
+        }
+
+        let timer_statuses_changed = self.update_timer_statuses(now);
+
+        expired_status_flags != 0 || message_expired || timer_statuses_changed
+    }
+
+    /// Updates the ship from the status file, returning true if the change is
+    /// relevant, i.e. a status flag or the level of an attribute has changed.
     pub fn update_status(&mut self, status: FileStatus) -> bool {
-        // Flatten non-flag statuses into the bit-field.
-        let incoming_status_flags = status.flags as u64
+        // Flatten both flag fields and non-flag statuses into the bit-field.
+        let incoming_status_flags = status.flags as StatusBitField
+            | (status.flags2 as StatusBitField) << FLAGS2_SHIFT
             | if status.legal_state == LegalState::Speeding {
                 SPEEDING
             } else {
@@ -258,17 +1102,65 @@ impl Ship {
             };
 
         let updated_status_flags = Self::filtered_status_flags(incoming_status_flags);
+        let status_flags_changed =
+            Self::filtered_status_flags(self.status_flags) != updated_status_flags;
+
+        // Values like heading change constantly so only a change in the level
+        // of an attribute that uses them in a rule is relevant.
+        let previous_status_levels = self.status_levels();
+        let pips_changed = self.file_status.pips != status.pips;
+        let gui_focus_changed = self.file_status.gui_focus != status.gui_focus;
+        let fire_group_changed = self.file_status.fire_group != status.fire_group;
+
+        // Reinstate derived status flags that were filtered out (and
+        // necessarily can't have triggered a status change).
+        self.status_flags = updated_status_flags | (self.status_flags & JOURNAL_STATUSES);
+        if let Some(fire_group) = status.fire_group {
+            self.fire_groups = self.fire_groups.max(fire_group.saturating_add(1));
+        }
+        self.file_status = status;
 
-        if Self::filtered_status_flags(self.status_flags) == updated_status_flags {
-            false
-        } else {
-            // Reinstate derived status flags that were filtered out (and
-            // necessarily can't have triggered a status change).
-            self.status_flags = updated_status_flags | (self.status_flags & DOCKING);
-            true
+        status_flags_changed
+            || pips_changed
+            || gui_focus_changed
+            || fire_group_changed
+            || self.status_levels() != previous_status_levels
+    }
+
+    /// Returns the given value if currently known. Fuel is the percentage of
+    /// the main tank capacity (known once the loadout is read from the
+    /// journal), oxygen and health are percentages and pips are whole pips.
+    fn value(&self, value: Value) -> Option<f64> {
+        let status = &self.file_status;
+        let pips = |pips: fn(&Pips) -> u8| status.pips.map(|p| pips(&p) as f64 / 2.0);
+
+        match value {
+            Value::Altitude => status.altitude,
+            Value::Cargo => status.cargo,
+            Value::EnginesPips => pips(|p| p.engines),
+            Value::FireGroup => status.fire_group.map(f64::from),
+            Value::FireGroups => Some(f64::from(self.fire_groups)),
+            Value::Fuel => match (status.fuel, self.fuel_capacity) {
+                (Some(fuel), Some(capacity)) if capacity > 0.0 => {
+                    Some(fuel.main / capacity * 100.0)
+                }
+                _ => None,
+            },
+            Value::FuelMain => status.fuel.map(|fuel| fuel.main),
+            Value::FuelReservoir => status.fuel.map(|fuel| fuel.reservoir),
+            Value::GuiFocus => status.gui_focus.map(|focus| u8::from(focus) as f64),
+            Value::Heading => status.heading,
+            Value::Health => status.health.map(|health| health * 100.0),
+            Value::Oxygen => status.oxygen.map(|oxygen| oxygen * 100.0),
+            Value::SystemsPips => pips(|p| p.systems),
+            Value::WeaponsPips => pips(|p| p.weapons),
         }
     }
 
+    fn status_levels(&self) -> Vec<StatusLevel> {
+        self.statuses().iter().map(|status| status.level).collect()
+    }
+
     #[cfg(test)]
     // Could refactor this into a private constructor instead.
     fn set_status(&mut self, status_flags: StatusBitField) {
@@ -293,12 +1185,37 @@ impl Ship {
 
     /// Returns the current global (highest precendence) status for the ship.
     pub fn global_status(&self) -> GlobalStatus {
-        if self.any_status_flags_set(NIGHT_VISION_ON) {
+        // An open panel or map takes over the controls whatever else is going
+        // on.
+        let gui_focus_global_status = match self.file_status.gui_focus {
+            Some(FileGuiFocus::GalaxyMap) => Some(GlobalStatus::GalaxyMap),
+            Some(FileGuiFocus::SystemMap) => Some(GlobalStatus::SystemMap),
+            Some(FileGuiFocus::FullSpectrumScanner) => Some(GlobalStatus::FullSpectrumScanner),
+            Some(FileGuiFocus::SurfaceAreaAnalysis) => Some(GlobalStatus::SurfaceAreaAnalysis),
+            Some(FileGuiFocus::StationServices) => Some(GlobalStatus::StationServices),
+            Some(FileGuiFocus::CommsPanel) => Some(GlobalStatus::CommsPanel),
+            _ => None,
+        };
+
+        if let Some(global_status) = gui_focus_global_status {
+            global_status
+        } else if self.any_status_flags_set(NIGHT_VISION_ON) {
             GlobalStatus::NightVisionOn
+        } else if self.any_status_flags_set(ON_FOOT) {
+            GlobalStatus::OnFoot
+        } else if self.any_status_flags_set(IN_SRV) {
+            GlobalStatus::InSrv
+        } else if self.in_fighter() {
+            GlobalStatus::InFighter
+        } else if self.any_status_flags_set(IN_MULTICREW | CREWING) {
+            GlobalStatus::Multicrew
         } else if self.any_status_flags_set(SUPERCRUISE) {
             // FSS scans while in supercruise can cause the hardpoint deployed
             // status to be set but we don't want this to be the global status.
             GlobalStatus::Normal
+        } else if self.all_status_flags_set(HARDPOINTS_DEPLOYED | HUD_IN_ANALYSIS_MODE) {
+            // Hardpoints in analysis mode are scanners rather than weapons.
+            GlobalStatus::HardpointsDeployedAnalysis
         } else if self.any_status_flags_set(HARDPOINTS_DEPLOYED) {
             GlobalStatus::HardpointsDeployed
         } else {
@@ -306,14 +1223,97 @@ impl Ship {
         }
     }
 
+    /// Returns the set of controls currently in use, e.g. those for on foot
+    /// when the commander has disembarked or for driving the SRV.
+    pub fn control_set(&self) -> ControlSet {
+        if self.any_status_flags_set(ON_FOOT) {
+            ControlSet::OnFoot
+        } else if self.any_status_flags_set(IN_SRV) {
+            ControlSet::Srv
+        } else {
+            ControlSet::Ship
+        }
+    }
+
+    /// Returns true if the commander is flying a ship-launched fighter. The
+    /// status flag is preferred but the launch event covers the period before
+    /// the status file catches up. Once back in the main ship the launch event
+    /// no longer applies, e.g. after the fighter is destroyed.
+    fn in_fighter(&self) -> bool {
+        self.any_status_flags_set(IN_FIGHTER)
+            || (self.any_status_flags_set(PILOTING_FIGHTER)
+                && !self.any_status_flags_set(IN_MAIN_SHIP))
+    }
+
+    /// Returns the mappings for the power to a system given the name of the
+    /// value holding its pips.
+    fn power_mappings(pips_name: &str) -> Vec<ConditionStatusLevelMapping> {
+        vec![
+            ConditionStatusLevelMapping::new(
+                Self::built_in_expression(&format!("{} = 4", pips_name)),
+                StatusLevel::Blocked,
+            ),
+            ConditionStatusLevelMapping::new(
+                Self::built_in_expression(&format!("{} > 2", pips_name)),
+                StatusLevel::Active,
+            ),
+        ]
+    }
+
+    /// Returns a condition for a built-in expression, which must be valid.
+    fn built_in_expression(source: &str) -> Condition {
+        Condition::When(Expression::parse(source, &NAMES).expect("Invalid built-in expression"))
+    }
+
+    /// Returns the currently selected fire group if known.
+    pub fn fire_group(&self) -> Option<u8> {
+        self.file_status.fire_group
+    }
+
+    /// Returns the current power distributor settings if known.
+    pub fn pips(&self) -> Option<Pips> {
+        self.file_status.pips
+    }
+
+    /// Returns a `Ship` instance using the given rules in place of the
+    /// built-in rules for each attribute they apply to. Rules are tested in
+    /// the order given. Derived statuses are raised using the given settings.
+    pub fn with_rules(rules: &[Rule], settings: ShipSettings) -> Self {
+        let mut ship = Self::new();
+        ship.settings = settings;
+
+        for mappings in &mut ship.attribute_status_level_mappings {
+            let configured_mappings: Vec<ConditionStatusLevelMapping> = rules
+                .iter()
+                .filter(|rule| rule.attribute == mappings.attribute)
+                .map(|rule| ConditionStatusLevelMapping::new(rule.condition.clone(), rule.level))
+                .collect();
+
+            if !configured_mappings.is_empty() {
+                info!("Using configured rules for {:?}", mappings.attribute);
+                mappings.condition_status_level_mappings = configured_mappings;
+            }
+        }
+
+        ship
+    }
+
     fn status_level_for_condition(
         &self,
         mappings: &Vec<ConditionStatusLevelMapping>,
     ) -> StatusLevel {
         for mapping in mappings {
-            if match mapping.condition {
-                Condition::Any(flags) => self.any_status_flags_set(flags),
-                Condition::All(flags) => self.all_status_flags_set(flags),
+            if match &mapping.condition {
+                Condition::Any(flags) => self.any_status_flags_set(*flags),
+                Condition::All(flags) => self.all_status_flags_set(*flags),
+                Condition::None(flags) => !self.any_status_flags_set(*flags),
+                Condition::When(expression) => {
+                    expression.evaluate(self.status_flags, &|value| self.value(value))
+                }
+                Condition::LowFuel => match self.value(Value::Fuel) {
+                    Some(percentage) => percentage < self.settings.low_fuel_fraction * 100.0,
+                    None => false,
+                },
             } {
                 return mapping.status_level;
             }
@@ -338,6 +1338,7 @@ impl Ship {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game::file::{journal::FuelCapacity, Fuel, GuiFocus};
 
     fn statuses() -> Vec<StatusBitField> {
         vec![
@@ -350,8 +1351,18 @@ mod tests {
             FRAME_SHIFT_DRIVE_COOLDOWN,
             OVERHEATING,
             HARDPOINTS_DEPLOYED,
+            FUEL_SCOOPING,
             SUPERCRUISE,
             NIGHT_VISION_ON,
+            SRV_HANDBRAKE,
+            SRV_TURRET_VIEW,
+            SRV_TURRET_RETRACTED,
+            SRV_DRIVE_ASSIST,
+            IN_SRV,
+            HUD_IN_ANALYSIS_MODE,
+            SRV_HIGH_BEAM,
+            IN_MAIN_SHIP,
+            IN_FIGHTER,
         ]
     }
 
@@ -361,22 +1372,64 @@ mod tests {
             let mut ship = Ship::new();
             ship.update_status(FileStatus {
                 flags: flag as u32,
+                flags2: 0,
                 legal_state: LegalState::Other,
+                ..Default::default()
             });
-            assert_eq!(ship.all_status_flags_set(flag), true);
+            assert!(ship.all_status_flags_set(flag));
         }
     }
 
     #[test]
-    fn ship_update_status_clears_statuses() {
-        for flag in statuses() {
-            let mut ship = Ship::new();
-            ship.set_status(flag);
-            ship.update_status(FileStatus {
-                flags: 0,
-                legal_state: LegalState::Other,
+    fn derived_statuses_are_packed_above_both_flag_fields() {
+        let flag_fields = u64::MAX as StatusBitField;
+        assert_eq!((JOURNAL_STATUSES | SPEEDING) & flag_fields, 0);
+    }
+
+    fn flags2_statuses() -> Vec<StatusBitField> {
+        vec![
+            ON_FOOT,
+            IN_TAXI,
+            IN_MULTICREW,
+            ON_FOOT_IN_STATION,
+            LOW_OXYGEN,
+            LOW_HEALTH,
+            COLD,
+            HOT,
+            VERY_COLD,
+            VERY_HOT,
+            GLIDE_MODE,
+            ON_FOOT_SOCIAL_SPACE,
+            BREATHABLE_ATMOSPHERE,
+        ]
+    }
+
+    #[test]
+    fn ship_update_status_sets_flags2_statuses() {
+        for flag in flags2_statuses() {
+            let mut ship = Ship::new();
+            assert!(ship.update_status(FileStatus {
+                flags: 0,
+                flags2: (flag >> FLAGS2_SHIFT) as u32,
+                legal_state: LegalState::Other,
+                ..Default::default()
+            }));
+            assert!(ship.all_status_flags_set(flag));
+        }
+    }
+
+    #[test]
+    fn ship_update_status_clears_statuses() {
+        for flag in statuses() {
+            let mut ship = Ship::new();
+            ship.set_status(flag);
+            ship.update_status(FileStatus {
+                flags: 0,
+                flags2: 0,
+                legal_state: LegalState::Other,
+                ..Default::default()
             });
-            assert_eq!(ship.all_status_flags_set(flag), false);
+            assert!(!ship.all_status_flags_set(flag));
         }
     }
 
@@ -384,20 +1437,18 @@ mod tests {
     fn ship_update_status_returns_true_on_change() {
         for flag in statuses() {
             let mut ship = Ship::new();
-            assert_eq!(
-                ship.update_status(FileStatus {
-                    flags: flag as u32,
-                    legal_state: LegalState::Other,
-                }),
-                true
-            );
-            assert_eq!(
-                ship.update_status(FileStatus {
-                    flags: flag as u32,
-                    legal_state: LegalState::Other,
-                }),
-                false
-            );
+            assert!(ship.update_status(FileStatus {
+                flags: flag as u32,
+                flags2: 0,
+                legal_state: LegalState::Other,
+                ..Default::default()
+            }));
+            assert!(!ship.update_status(FileStatus {
+                flags: flag as u32,
+                flags2: 0,
+                legal_state: LegalState::Other,
+                ..Default::default()
+            }));
         }
     }
 
@@ -407,21 +1458,38 @@ mod tests {
         ship.set_status(DOCKING);
         ship.update_status(FileStatus {
             flags: LANDING_GEAR_DEPLOYED as u32,
+            flags2: 0,
             legal_state: LegalState::Other,
+            ..Default::default()
         });
-        assert_eq!(ship.all_status_flags_set(DOCKING), true);
+        assert!(ship.all_status_flags_set(DOCKING));
     }
 
-    fn assert_status(status_flags: StatusBitField, attribute: Attribute, level: StatusLevel) {
+    #[test]
+    fn ship_update_status_does_not_clobber_journal_states() {
         let mut ship = Ship::new();
-        ship.set_status(status_flags);
-        let statuses = ship.statuses();
-        let status = statuses
+        ship.set_status(PILOTING_FIGHTER | CREWING);
+        ship.update_status(FileStatus {
+            flags: LANDING_GEAR_DEPLOYED as u32,
+            flags2: 0,
+            legal_state: LegalState::Other,
+            ..Default::default()
+        });
+        assert!(ship.all_status_flags_set(PILOTING_FIGHTER | CREWING));
+    }
+
+    fn status_level(ship: &Ship, attribute: Attribute) -> StatusLevel {
+        ship.statuses()
             .iter()
             .find(|&status| status.attribute == attribute)
-            .expect("Statuses did not include expected attribute");
+            .expect("Statuses did not include expected attribute")
+            .level
+    }
 
-        assert_eq!(status.level, level);
+    fn assert_status(status_flags: StatusBitField, attribute: Attribute, level: StatusLevel) {
+        let mut ship = Ship::new();
+        ship.set_status(status_flags);
+        assert_eq!(status_level(&ship, attribute), level);
     }
 
     #[test]
@@ -429,9 +1497,11 @@ mod tests {
         let mut ship = Ship::new();
         ship.update_status(FileStatus {
             flags: 0,
+            flags2: 0,
             legal_state: LegalState::Speeding,
+            ..Default::default()
         });
-        assert_eq!(ship.all_status_flags_set(SPEEDING), true);
+        assert!(ship.all_status_flags_set(SPEEDING));
     }
 
     #[test]
@@ -439,28 +1509,28 @@ mod tests {
         let mut ship = Ship::new();
         ship.update_status(FileStatus {
             flags: 0,
+            flags2: 0,
             legal_state: LegalState::Other,
+            ..Default::default()
         });
-        assert_eq!(ship.all_status_flags_set(SPEEDING), false);
+        assert!(!ship.all_status_flags_set(SPEEDING));
     }
 
     #[test]
     fn ship_update_speeding_returns_true_on_change() {
         let mut ship = Ship::new();
-        assert_eq!(
-            ship.update_status(FileStatus {
-                flags: 0,
-                legal_state: LegalState::Speeding,
-            }),
-            true
-        );
-        assert_eq!(
-            ship.update_status(FileStatus {
-                flags: 0,
-                legal_state: LegalState::Speeding,
-            }),
-            false
-        );
+        assert!(ship.update_status(FileStatus {
+            flags: 0,
+            flags2: 0,
+            legal_state: LegalState::Speeding,
+            ..Default::default()
+        }));
+        assert!(!ship.update_status(FileStatus {
+            flags: 0,
+            flags2: 0,
+            legal_state: LegalState::Speeding,
+            ..Default::default()
+        }));
     }
 
     #[test]
@@ -469,9 +1539,11 @@ mod tests {
         ship.set_status(DOCKING);
         ship.update_status(FileStatus {
             flags: 0,
+            flags2: 0,
             legal_state: LegalState::Speeding,
+            ..Default::default()
         });
-        assert_eq!(ship.all_status_flags_set(DOCKING), true);
+        assert!(ship.all_status_flags_set(DOCKING));
     }
 
     #[test]
@@ -664,6 +1736,35 @@ mod tests {
         assert_status(SPEEDING, Attribute::Throttle, StatusLevel::Alert);
     }
 
+    #[test]
+    fn low_health_maps_to_health_alert() {
+        assert_status(LOW_HEALTH, Attribute::Health, StatusLevel::Alert);
+    }
+
+    #[test]
+    fn on_foot_maps_to_oxygen_active_unless_atmosphere_breathable() {
+        assert_status(ON_FOOT, Attribute::Oxygen, StatusLevel::Active);
+        assert_status(
+            ON_FOOT | BREATHABLE_ATMOSPHERE,
+            Attribute::Oxygen,
+            StatusLevel::Inactive,
+        );
+        assert_status(ON_FOOT | LOW_OXYGEN, Attribute::Oxygen, StatusLevel::Alert);
+    }
+
+    #[test]
+    fn cold_or_hot_maps_to_temperature_active() {
+        assert_status(COLD, Attribute::Temperature, StatusLevel::Active);
+        assert_status(HOT, Attribute::Temperature, StatusLevel::Active);
+        assert_status(VERY_COLD, Attribute::Temperature, StatusLevel::Alert);
+        assert_status(VERY_HOT, Attribute::Temperature, StatusLevel::Alert);
+    }
+
+    #[test]
+    fn glide_mode_maps_to_glide_active() {
+        assert_status(GLIDE_MODE, Attribute::Glide, StatusLevel::Active);
+    }
+
     #[test]
     fn supercruise_and_hardpoints_deployed_and_maps_to_frame_shift_drive_inactive() {
         assert_status(
@@ -684,6 +1785,765 @@ mod tests {
         assert_global_status(0, GlobalStatus::Normal);
         assert_global_status(HARDPOINTS_DEPLOYED, GlobalStatus::HardpointsDeployed);
         assert_global_status(HARDPOINTS_DEPLOYED | SUPERCRUISE, GlobalStatus::Normal);
+        assert_global_status(HUD_IN_ANALYSIS_MODE, GlobalStatus::Normal);
+        assert_global_status(
+            HARDPOINTS_DEPLOYED | HUD_IN_ANALYSIS_MODE,
+            GlobalStatus::HardpointsDeployedAnalysis,
+        );
+        assert_global_status(
+            HARDPOINTS_DEPLOYED | HUD_IN_ANALYSIS_MODE | SUPERCRUISE,
+            GlobalStatus::Normal,
+        );
         assert_global_status(NIGHT_VISION_ON, GlobalStatus::NightVisionOn);
+        assert_global_status(ON_FOOT, GlobalStatus::OnFoot);
+        assert_global_status(ON_FOOT | NIGHT_VISION_ON, GlobalStatus::NightVisionOn);
+        assert_global_status(IN_SRV, GlobalStatus::InSrv);
+        assert_global_status(IN_SRV | NIGHT_VISION_ON, GlobalStatus::NightVisionOn);
+        assert_global_status(IN_FIGHTER, GlobalStatus::InFighter);
+        assert_global_status(PILOTING_FIGHTER, GlobalStatus::InFighter);
+        assert_global_status(PILOTING_FIGHTER | IN_MAIN_SHIP, GlobalStatus::Normal);
+        assert_global_status(IN_MULTICREW, GlobalStatus::Multicrew);
+        assert_global_status(CREWING, GlobalStatus::Multicrew);
+        assert_global_status(IN_FIGHTER | CREWING, GlobalStatus::InFighter);
+    }
+
+    #[test]
+    fn gui_focus_global_statuses_take_precedence() {
+        let gui_focus_status = |gui_focus| FileStatus {
+            flags: (NIGHT_VISION_ON | HARDPOINTS_DEPLOYED) as u32,
+            gui_focus: Some(gui_focus),
+            ..Default::default()
+        };
+        let mut ship = Ship::new();
+
+        for (gui_focus, global_status) in [
+            (GuiFocus::GalaxyMap, GlobalStatus::GalaxyMap),
+            (GuiFocus::SystemMap, GlobalStatus::SystemMap),
+            (
+                GuiFocus::FullSpectrumScanner,
+                GlobalStatus::FullSpectrumScanner,
+            ),
+            (
+                GuiFocus::SurfaceAreaAnalysis,
+                GlobalStatus::SurfaceAreaAnalysis,
+            ),
+            (GuiFocus::StationServices, GlobalStatus::StationServices),
+            (GuiFocus::CommsPanel, GlobalStatus::CommsPanel),
+            (GuiFocus::InternalPanel, GlobalStatus::NightVisionOn),
+            (GuiFocus::NoFocus, GlobalStatus::NightVisionOn),
+        ] {
+            assert!(ship.update_status(gui_focus_status(gui_focus)));
+            assert_eq!(ship.global_status(), global_status);
+        }
+    }
+
+    #[test]
+    fn hud_mode_active_in_analysis_mode() {
+        assert_status(0, Attribute::HudMode, StatusLevel::Inactive);
+        assert_status(
+            HUD_IN_ANALYSIS_MODE,
+            Attribute::HudMode,
+            StatusLevel::Active,
+        );
+    }
+
+    #[test]
+    fn ui_navigation_active_while_a_panel_is_open() {
+        let mut ship = Ship::new();
+        assert_eq!(
+            status_level(&ship, Attribute::UiNavigation),
+            StatusLevel::Inactive
+        );
+
+        ship.update_status(FileStatus {
+            gui_focus: Some(GuiFocus::RolePanel),
+            ..Default::default()
+        });
+        assert_eq!(
+            status_level(&ship, Attribute::UiNavigation),
+            StatusLevel::Active
+        );
+
+        ship.update_status(FileStatus {
+            gui_focus: Some(GuiFocus::NoFocus),
+            ..Default::default()
+        });
+        assert_eq!(
+            status_level(&ship, Attribute::UiNavigation),
+            StatusLevel::Inactive
+        );
+    }
+
+    #[test]
+    fn status_names_are_unique_and_map_to_distinct_statuses() {
+        for (index, (name, flag)) in STATUS_NAMES.iter().enumerate() {
+            for (other_name, other_flag) in &STATUS_NAMES[index + 1..] {
+                assert_ne!(name, other_name);
+                assert_ne!(flag, other_flag);
+            }
+        }
+    }
+
+    fn rule_from_toml(toml: &str) -> Result<Rule, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    #[test]
+    fn rule_from_toml_maps_status_names_to_condition() {
+        assert_eq!(
+            rule_from_toml(
+                r#"
+                attribute = "boost"
+                any = ["cargo-scoop-deployed", "mass-locked"]
+                level = "blocked""#
+            )
+            .unwrap(),
+            Rule {
+                attribute: Attribute::Boost,
+                condition: Condition::Any(CARGO_SCOOP_DEPLOYED | MASS_LOCKED),
+                level: StatusLevel::Blocked,
+            }
+        );
+    }
+
+    #[test]
+    fn rule_from_toml_rejects_unknown_names() {
+        let error = rule_from_toml(
+            r#"
+            attribute = "boost"
+            none = ["warp-drive"]
+            level = "blocked""#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("unknown status `warp-drive`"));
+
+        assert!(rule_from_toml(
+            r#"
+            attribute = "warp-drive"
+            all = ["mass-locked"]
+            level = "blocked""#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rule_from_toml_parses_expression_conditions() {
+        let rule = rule_from_toml(
+            r#"
+            attribute = "frame-shift-drive"
+            when = "frame_shift_drive_charging and not (supercruise or mass-locked)"
+            level = "active""#,
+        )
+        .unwrap();
+        let mut ship = Ship::with_rules(&[rule], ShipSettings::default());
+
+        ship.set_status(FRAME_SHIFT_DRIVE_CHARGING);
+        assert_eq!(
+            status_level(&ship, Attribute::FrameShiftDrive),
+            StatusLevel::Active
+        );
+        ship.set_status(FRAME_SHIFT_DRIVE_CHARGING | MASS_LOCKED);
+        assert_eq!(
+            status_level(&ship, Attribute::FrameShiftDrive),
+            StatusLevel::Inactive
+        );
+    }
+
+    #[test]
+    fn rule_from_toml_reports_expression_errors_with_column() {
+        let error = rule_from_toml(
+            r#"
+            attribute = "boost"
+            when = "mass-locked and (cargo-scoop-deployed"
+            level = "blocked""#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains(
+            "invalid expression `mass-locked and (cargo-scoop-deployed`: unclosed `(` at column 17"
+        ));
+    }
+
+    fn fuel_status(main: f64) -> FileStatus {
+        FileStatus {
+            fuel: Some(Fuel {
+                main,
+                reservoir: 0.5,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ship_value_returns_values_from_status_and_loadout() {
+        let mut ship = Ship::new();
+        ship.update_status(FileStatus {
+            pips: Some(Pips {
+                systems: 2,
+                engines: 3,
+                weapons: 7,
+            }),
+            health: Some(0.5),
+            gui_focus: Some(GuiFocus::GalaxyMap),
+            ..fuel_status(8.0)
+        });
+
+        assert_eq!(ship.value(Value::FuelMain), Some(8.0));
+        assert_eq!(ship.value(Value::FuelReservoir), Some(0.5));
+        assert_eq!(ship.value(Value::EnginesPips), Some(1.5));
+        assert_eq!(ship.value(Value::WeaponsPips), Some(3.5));
+        assert_eq!(ship.value(Value::Health), Some(50.0));
+        assert_eq!(ship.value(Value::GuiFocus), Some(6.0));
+        assert_eq!(ship.value(Value::Cargo), None);
+        assert_eq!(ship.value(Value::Fuel), None);
+
+        ship.apply_journal_event(Event::Loadout {
+            fuel_capacity: Some(FuelCapacity {
+                main: 32.0,
+                reserve: 0.63,
+            }),
+        });
+        assert_eq!(ship.value(Value::Fuel), Some(25.0));
+    }
+
+    #[test]
+    fn pips_map_to_power_attributes() {
+        let mut ship = Ship::new();
+        assert!(ship.update_status(FileStatus {
+            pips: Some(Pips {
+                systems: 8,
+                engines: 4,
+                weapons: 0,
+            }),
+            ..Default::default()
+        }));
+
+        assert_eq!(
+            status_level(&ship, Attribute::SystemsPower),
+            StatusLevel::Blocked
+        );
+        assert_eq!(
+            status_level(&ship, Attribute::EnginesPower),
+            StatusLevel::Inactive
+        );
+        assert_eq!(
+            status_level(&ship, Attribute::WeaponsPower),
+            StatusLevel::Inactive
+        );
+        assert_eq!(
+            status_level(&ship, Attribute::PowerDistribution),
+            StatusLevel::Active
+        );
+
+        assert!(ship.update_status(FileStatus {
+            pips: Some(Pips {
+                systems: 4,
+                engines: 5,
+                weapons: 3,
+            }),
+            ..Default::default()
+        }));
+        assert_eq!(
+            status_level(&ship, Attribute::EnginesPower),
+            StatusLevel::Active
+        );
+    }
+
+    #[test]
+    fn fire_groups_active_once_a_later_group_is_selected_until_loadout_changes() {
+        let fire_group_status = |fire_group| FileStatus {
+            fire_group: Some(fire_group),
+            ..Default::default()
+        };
+        let mut ship = Ship::new();
+
+        ship.update_status(fire_group_status(0));
+        assert_eq!(
+            status_level(&ship, Attribute::FireGroups),
+            StatusLevel::Inactive
+        );
+        assert!(ship.update_status(fire_group_status(2)));
+        assert_eq!(
+            status_level(&ship, Attribute::FireGroups),
+            StatusLevel::Active
+        );
+        assert!(ship.update_status(fire_group_status(0)));
+        assert_eq!(
+            status_level(&ship, Attribute::FireGroups),
+            StatusLevel::Active
+        );
+
+        ship.apply_journal_event(Event::Loadout {
+            fuel_capacity: None,
+        });
+        ship.update_status(fire_group_status(0));
+        assert_eq!(
+            status_level(&ship, Attribute::FireGroups),
+            StatusLevel::Inactive
+        );
+    }
+
+    #[test]
+    fn danger_flags_alert_on_throttle_and_boost() {
+        for status_flags in [IN_DANGER, BEING_INTERDICTED, IN_DANGER | SPEEDING] {
+            assert_status(status_flags, Attribute::Throttle, StatusLevel::Alert);
+            assert_status(status_flags, Attribute::Boost, StatusLevel::Alert);
+        }
+        assert_status(
+            LANDING_GEAR_DEPLOYED | IN_DANGER,
+            Attribute::Boost,
+            StatusLevel::Alert,
+        );
+    }
+
+    #[test]
+    fn danger_journal_events_alert_until_alert_duration_passes() {
+        let alert_duration = Duration::from_secs(5);
+        let mut ship = Ship::with_rules(
+            &[],
+            ShipSettings {
+                alert_duration,
+                ..Default::default()
+            },
+        );
+
+        for event in [
+            Event::Interdicted,
+            Event::UnderAttack,
+            Event::HullDamage,
+            Event::HeatWarning,
+            Event::ShieldState { shields_up: false },
+        ] {
+            ship.apply_journal_event(event);
+            let alert_raised = Instant::now();
+
+            // Survives status updates until expired.
+            ship.update_status(FileStatus::default());
+            assert_eq!(status_level(&ship, Attribute::Throttle), StatusLevel::Alert);
+            assert_eq!(status_level(&ship, Attribute::Boost), StatusLevel::Alert);
+            assert!(!ship.update_time(alert_raised));
+
+            assert!(ship.update_time(alert_raised + alert_duration));
+            assert_eq!(
+                status_level(&ship, Attribute::Throttle),
+                StatusLevel::Inactive
+            );
+            assert!(!ship.update_time(alert_raised + alert_duration));
+        }
+    }
+
+    #[test]
+    fn interdicting_another_ship_does_not_alert() {
+        let mut ship = Ship::new();
+
+        ship.apply_journal_event(Event::Interdiction);
+
+        assert!(!ship.any_status_flags_set(DANGER_ALERT));
+        assert_eq!(
+            status_level(&ship, Attribute::Throttle),
+            StatusLevel::Inactive
+        );
+    }
+
+    #[test]
+    fn escaping_interdiction_clears_danger_alert() {
+        let mut ship = Ship::new();
+
+        ship.apply_journal_event(Event::Interdicted);
+        ship.apply_journal_event(Event::EscapeInterdiction);
+        // The interdiction message expires independently of the alert.
+        ship.message = None;
+
+        assert_eq!(
+            status_level(&ship, Attribute::Throttle),
+            StatusLevel::Inactive
+        );
+        assert!(!ship.update_time(Instant::now() + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn journal_events_raise_timed_statuses_until_expired() {
+        for (event, status_flag) in [
+            (Event::ShieldState { shields_up: true }, SHIELDS_RESTORED),
+            (
+                Event::FsdJump {
+                    star_system: String::from("A"),
+                },
+                JUMPED,
+            ),
+            (Event::Scan, SCAN_COMPLETE),
+            (Event::SaaScanComplete, SCAN_COMPLETE),
+            (Event::HeatSinkLaunched, HEAT_SINK_LAUNCHED),
+        ] {
+            let mut ship = Ship::new();
+
+            ship.apply_journal_event(event);
+            let raised = Instant::now();
+            ship.update_status(FileStatus::default());
+            assert!(ship.all_status_flags_set(status_flag));
+            assert!(!ship.update_time(raised));
+
+            assert!(ship.update_time(raised + TIMED_STATUS_DURATION));
+            assert!(!ship.any_status_flags_set(status_flag));
+        }
+    }
+
+    #[test]
+    fn timed_statuses_expire_independently() {
+        let alert_duration = Duration::from_secs(10);
+        let mut ship = Ship::with_rules(
+            &[],
+            ShipSettings {
+                alert_duration,
+                ..Default::default()
+            },
+        );
+
+        ship.apply_journal_event(Event::UnderAttack);
+        ship.apply_journal_event(Event::FsdJump {
+            star_system: String::from("A"),
+        });
+        let raised = Instant::now();
+
+        assert!(ship.update_time(raised + TIMED_STATUS_DURATION));
+        assert!(!ship.any_status_flags_set(JUMPED));
+        assert!(ship.all_status_flags_set(DANGER_ALERT));
+
+        assert!(ship.update_time(raised + alert_duration));
+        assert!(!ship.any_status_flags_set(DANGER_ALERT));
+    }
+
+    #[test]
+    fn journal_events_set_text_for_display() {
+        let mut ship = Ship::new();
+        assert_eq!(ship.text("system"), None);
+
+        ship.apply_journal_event(Event::Location {
+            star_system: String::from("Sol"),
+        });
+        ship.apply_journal_event(Event::FsdTarget {
+            name: String::from("Alpha Centauri"),
+        });
+        assert_eq!(ship.text("system"), Some(String::from("Sol")));
+        assert_eq!(ship.text("target"), Some(String::from("Alpha Centauri")));
+
+        ship.apply_journal_event(Event::FsdJump {
+            star_system: String::from("Alpha Centauri"),
+        });
+        assert_eq!(ship.text("system"), Some(String::from("Alpha Centauri")));
+    }
+
+    #[test]
+    fn text_rounds_numeric_values() {
+        let mut ship = Ship::new();
+        ship.update_status(FileStatus {
+            cargo: Some(12.6),
+            ..Default::default()
+        });
+
+        assert_eq!(ship.text("cargo"), Some(String::from("13")));
+        assert!(Ship::is_text_name("cargo"));
+        assert!(!Ship::is_text_name("cargo-hold"));
+    }
+
+    #[test]
+    fn journal_event_messages_expire() {
+        let mut ship = Ship::new();
+
+        ship.apply_journal_event(Event::DockingGranted {
+            landing_pad: Some(14),
+            timestamp: None,
+        });
+        let shown = Instant::now();
+        assert_eq!(ship.message(), Some("Docking: pad 14"));
+        assert!(!ship.update_time(shown));
+
+        assert!(ship.update_time(shown + MESSAGE_DURATION));
+        assert_eq!(ship.message(), None);
+    }
+
+    #[test]
+    fn docking_granted_starts_docking_timer_until_docked() {
+        let mut ship = Ship::with_rules(
+            &[],
+            ShipSettings {
+                timer_warning: Duration::from_secs(60),
+                ..Default::default()
+            },
+        );
+
+        ship.apply_journal_event(Event::DockingGranted {
+            landing_pad: None,
+            timestamp: Some(SystemTime::now()),
+        });
+        let started = Instant::now();
+        assert_eq!(status_level(&ship, Attribute::Timers), StatusLevel::Active);
+        assert_eq!(
+            ship.timer_text(Timer::Docking, started),
+            Some(String::from("10:00"))
+        );
+
+        assert!(ship.update_time(started + Duration::from_secs(541)));
+        assert_eq!(status_level(&ship, Attribute::Timers), StatusLevel::Alert);
+        assert_eq!(
+            ship.timer_text(Timer::Docking, started + Duration::from_secs(541)),
+            Some(String::from("0:59"))
+        );
+
+        ship.apply_journal_event(Event::Docked);
+        assert_eq!(
+            status_level(&ship, Attribute::Timers),
+            StatusLevel::Inactive
+        );
+        assert_eq!(ship.text("docking-timer"), None);
+    }
+
+    #[test]
+    fn timers_expire() {
+        let mut ship = Ship::new();
+
+        ship.apply_journal_event(Event::CarrierJumpRequest {
+            departure_time: Some(SystemTime::now() + Duration::from_secs(3600)),
+        });
+        let started = Instant::now();
+        assert_eq!(
+            ship.timer_text(Timer::CarrierJump, started),
+            Some(String::from("1:00:00"))
+        );
+
+        assert!(ship.update_time(started + Duration::from_secs(3600)));
+        assert_eq!(
+            status_level(&ship, Attribute::Timers),
+            StatusLevel::Inactive
+        );
+        assert_eq!(ship.timer_text(Timer::CarrierJump, started), None);
+    }
+
+    #[test]
+    fn timers_are_not_started_for_old_journal_events() {
+        let mut ship = Ship::new();
+
+        ship.apply_journal_event(Event::DockingGranted {
+            landing_pad: None,
+            timestamp: Some(SystemTime::now() - DOCKING_TIMEOUT),
+        });
+
+        assert_eq!(
+            status_level(&ship, Attribute::Timers),
+            StatusLevel::Inactive
+        );
+        assert_eq!(ship.text("docking-timer"), None);
+        assert!(Ship::is_text_name("docking-timer"));
+    }
+
+    #[test]
+    fn shield_state_sets_and_clears_shields_down() {
+        let mut ship = Ship::new();
+
+        ship.apply_journal_event(Event::ShieldState { shields_up: false });
+        ship.update_status(FileStatus::default());
+        assert!(ship.all_status_flags_set(SHIELDS_DOWN));
+
+        ship.apply_journal_event(Event::ShieldState { shields_up: true });
+        assert!(!ship.any_status_flags_set(SHIELDS_DOWN));
+    }
+
+    #[test]
+    fn fuel_scoop_alerts_below_low_fuel_fraction_unless_scooping() {
+        let mut ship = Ship::with_rules(
+            &[],
+            ShipSettings {
+                low_fuel_fraction: 0.5,
+                ..Default::default()
+            },
+        );
+        ship.apply_journal_event(Event::Loadout {
+            fuel_capacity: Some(FuelCapacity {
+                main: 32.0,
+                reserve: 0.63,
+            }),
+        });
+
+        ship.update_status(fuel_status(16.0));
+        assert_eq!(
+            status_level(&ship, Attribute::FuelScoop),
+            StatusLevel::Inactive
+        );
+        ship.update_status(fuel_status(15.9));
+        assert_eq!(
+            status_level(&ship, Attribute::FuelScoop),
+            StatusLevel::Alert
+        );
+        ship.update_status(FileStatus {
+            flags: FUEL_SCOOPING as u32,
+            ..fuel_status(15.9)
+        });
+        assert_eq!(
+            status_level(&ship, Attribute::FuelScoop),
+            StatusLevel::Active
+        );
+    }
+
+    #[test]
+    fn fuel_scoop_journal_event_is_active_until_expired() {
+        let mut ship = Ship::new();
+        ship.apply_journal_event(Event::FuelScoop);
+        let raised = Instant::now();
+        ship.update_status(fuel_status(16.0));
+        assert_eq!(
+            status_level(&ship, Attribute::FuelScoop),
+            StatusLevel::Active
+        );
+        assert!(ship.update_time(raised + TIMED_STATUS_DURATION));
+        assert_eq!(
+            status_level(&ship, Attribute::FuelScoop),
+            StatusLevel::Inactive
+        );
+    }
+
+    #[test]
+    fn ship_update_status_is_relevant_when_a_value_changes_a_level() {
+        let rule = rule_from_toml(
+            r#"
+            attribute = "throttle"
+            when = "fuel-main < 4"
+            level = "alert""#,
+        )
+        .unwrap();
+        let mut ship = Ship::with_rules(&[rule], ShipSettings::default());
+
+        assert!(!ship.update_status(fuel_status(8.0)));
+        assert!(!ship.update_status(fuel_status(6.0)));
+        assert!(ship.update_status(fuel_status(3.0)));
+        assert_eq!(status_level(&ship, Attribute::Throttle), StatusLevel::Alert);
+        assert!(!ship.update_status(fuel_status(2.0)));
+    }
+
+    #[test]
+    fn ship_with_rules_replaces_built_in_rules_for_configured_attributes() {
+        let rules = vec![
+            Rule {
+                attribute: Attribute::Boost,
+                condition: Condition::All(CARGO_SCOOP_DEPLOYED),
+                level: StatusLevel::Blocked,
+            },
+            Rule {
+                attribute: Attribute::Boost,
+                condition: Condition::None(LANDING_GEAR_DEPLOYED),
+                level: StatusLevel::Active,
+            },
+        ];
+        let mut ship = Ship::with_rules(&rules, ShipSettings::default());
+
+        ship.set_status(CARGO_SCOOP_DEPLOYED);
+        assert_eq!(status_level(&ship, Attribute::Boost), StatusLevel::Blocked);
+        ship.set_status(0);
+        assert_eq!(status_level(&ship, Attribute::Boost), StatusLevel::Active);
+        ship.set_status(LANDING_GEAR_DEPLOYED);
+        assert_eq!(status_level(&ship, Attribute::Boost), StatusLevel::Inactive);
+
+        // Attributes without configured rules keep the built-in rules.
+        assert_eq!(
+            status_level(&ship, Attribute::CargoScoop),
+            StatusLevel::Inactive
+        );
+    }
+
+    #[test]
+    fn fighter_journal_events_set_and_clear_global_status() {
+        let mut ship = Ship::new();
+        ship.apply_journal_event(Event::LaunchFighter {
+            player_controlled: false,
+        });
+        assert_eq!(ship.global_status(), GlobalStatus::Normal);
+        ship.apply_journal_event(Event::LaunchFighter {
+            player_controlled: true,
+        });
+        assert_eq!(ship.global_status(), GlobalStatus::InFighter);
+        ship.apply_journal_event(Event::DockFighter);
+        assert_eq!(ship.global_status(), GlobalStatus::Normal);
+    }
+
+    #[test]
+    fn multicrew_journal_events_set_and_clear_global_status() {
+        let mut ship = Ship::new();
+        ship.apply_journal_event(Event::JoinACrew);
+        assert_eq!(ship.global_status(), GlobalStatus::Multicrew);
+        ship.apply_journal_event(Event::QuitACrew);
+        assert_eq!(ship.global_status(), GlobalStatus::Normal);
+        ship.apply_journal_event(Event::CrewMemberJoins);
+        assert_eq!(ship.global_status(), GlobalStatus::Multicrew);
+        ship.apply_journal_event(Event::CrewMemberQuits);
+        assert_eq!(ship.global_status(), GlobalStatus::Normal);
+    }
+
+    #[test]
+    fn multicrew_lasts_until_last_crew_member_quits() {
+        let mut ship = Ship::new();
+        ship.apply_journal_event(Event::CrewMemberJoins);
+        ship.apply_journal_event(Event::CrewMemberJoins);
+        ship.apply_journal_event(Event::CrewMemberQuits);
+        assert_eq!(ship.global_status(), GlobalStatus::Multicrew);
+        ship.apply_journal_event(Event::CrewMemberQuits);
+        assert_eq!(ship.global_status(), GlobalStatus::Normal);
+    }
+
+    #[test]
+    fn control_set_depends_on_being_on_foot_or_in_srv() {
+        let mut ship = Ship::new();
+        assert_eq!(ship.control_set(), ControlSet::Ship);
+        ship.set_status(ON_FOOT);
+        assert_eq!(ship.control_set(), ControlSet::OnFoot);
+        ship.set_status(IN_SRV);
+        assert_eq!(ship.control_set(), ControlSet::Srv);
+    }
+
+    #[test]
+    fn srv_statuses_map_to_srv_attributes() {
+        assert_status(
+            IN_SRV | SRV_DRIVE_ASSIST,
+            Attribute::SrvDriveAssist,
+            StatusLevel::Active,
+        );
+        assert_status(
+            IN_SRV | SRV_HANDBRAKE,
+            Attribute::SrvHandbrake,
+            StatusLevel::Active,
+        );
+        assert_status(
+            IN_SRV | SRV_TURRET_VIEW,
+            Attribute::SrvTurret,
+            StatusLevel::Active,
+        );
+        assert_status(
+            IN_SRV | SRV_TURRET_RETRACTED,
+            Attribute::SrvTurret,
+            StatusLevel::Blocked,
+        );
+        assert_status(
+            IN_SRV | SRV_HIGH_BEAM,
+            Attribute::ExternalLights,
+            StatusLevel::Active,
+        );
+        assert_status(IN_SRV, Attribute::SrvShip, StatusLevel::Inactive);
+        assert_status(
+            IN_SRV | SRV_TURRET_RETRACTED,
+            Attribute::SrvShip,
+            StatusLevel::Active,
+        );
+    }
+
+    #[test]
+    fn on_foot_in_station_maps_to_suit_equipment_blocked() {
+        assert_status(0, Attribute::SuitEquipment, StatusLevel::Inactive);
+        assert_status(
+            ON_FOOT | ON_FOOT_IN_STATION,
+            Attribute::SuitEquipment,
+            StatusLevel::Blocked,
+        );
+        assert_status(
+            ON_FOOT | ON_FOOT_SOCIAL_SPACE,
+            Attribute::SuitEquipment,
+            StatusLevel::Blocked,
+        );
     }
 }
@@ -0,0 +1,453 @@
+use super::{StatusBitField, Value};
+use std::fmt;
+
+/// A boolean expression over named statuses and comparisons of named values,
+/// e.g. `frame-shift-drive-charging and not (supercruise or fuel < 25)`. The
+/// operators in order of increasing precedence are `or`, `and` and `not`, and
+/// parentheses can be used for grouping.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    Status(StatusBitField),
+    Compare(Value, Comparison, f64),
+    Not(Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+}
+
+/// A comparison of a value against a number.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn compare(&self, left: f64, right: f64) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        }
+    }
+}
+
+/// The names that can be used in an expression: statuses, values that can be
+/// compared and named numbers to compare them with.
+pub struct Names<'a> {
+    pub statuses: &'a [(&'a str, StatusBitField)],
+    pub values: &'a [(&'a str, Value)],
+    pub constants: &'a [(&'a str, f64)],
+}
+
+impl Expression {
+    /// Returns the expression parsed from the given source, looking up names in
+    /// the given lists. Underscores in names are treated as hyphens.
+    pub fn parse(source: &str, names: &Names) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            tokens: tokens(source)?,
+            position: 0,
+            names,
+        };
+        let expression = parser.or_expression()?;
+
+        match parser.next() {
+            (Token::End, _) => Ok(expression),
+            (token, column) => Err(ParseError::new(
+                format!("unexpected {} after the end of the expression", token),
+                column,
+            )),
+        }
+    }
+
+    /// Returns true if the expression holds for the given status flags and
+    /// values. Comparisons of values that aren't known never hold.
+    pub fn evaluate<F>(&self, flags: StatusBitField, value: &F) -> bool
+    where
+        F: Fn(Value) -> Option<f64>,
+    {
+        match self {
+            Expression::Status(flag) => flags & flag != 0,
+            Expression::Compare(name, comparison, number) => match value(*name) {
+                Some(actual) => comparison.compare(actual, *number),
+                None => false,
+            },
+            Expression::Not(expression) => !expression.evaluate(flags, value),
+            Expression::And(left, right) => {
+                left.evaluate(flags, value) && right.evaluate(flags, value)
+            }
+            Expression::Or(left, right) => {
+                left.evaluate(flags, value) || right.evaluate(flags, value)
+            }
+        }
+    }
+}
+
+/// An error describing why an expression could not be parsed and the column
+/// (counting from one) where the problem was found.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    message: String,
+    column: usize,
+}
+
+impl ParseError {
+    fn new(message: String, column: usize) -> Self {
+        Self { message, column }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.column)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    LeftParenthesis,
+    RightParenthesis,
+    And,
+    Or,
+    Not,
+    Compare(Comparison),
+    Name(String),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::LeftParenthesis => write!(f, "`(`"),
+            Token::RightParenthesis => write!(f, "`)`"),
+            Token::And => write!(f, "`and`"),
+            Token::Or => write!(f, "`or`"),
+            Token::Not => write!(f, "`not`"),
+            Token::Compare(comparison) => write!(
+                f,
+                "`{}`",
+                match comparison {
+                    Comparison::Less => "<",
+                    Comparison::LessOrEqual => "<=",
+                    Comparison::Greater => ">",
+                    Comparison::GreaterOrEqual => ">=",
+                    Comparison::Equal => "=",
+                    Comparison::NotEqual => "!=",
+                }
+            ),
+            Token::Name(name) => write!(f, "`{}`", name),
+            Token::End => write!(f, "end of expression"),
+        }
+    }
+}
+
+/// Returns the tokens in the source, each with its column, ending with
+/// `Token::End`.
+fn tokens(source: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().enumerate().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        let column = index + 1;
+
+        match c {
+            '(' => tokens.push((Token::LeftParenthesis, column)),
+            ')' => tokens.push((Token::RightParenthesis, column)),
+            '<' | '>' | '=' | '!' => {
+                let or_equal = chars.next_if(|&(_, c)| c == '=').is_some();
+                let comparison = match (c, or_equal) {
+                    ('<', false) => Comparison::Less,
+                    ('<', true) => Comparison::LessOrEqual,
+                    ('>', false) => Comparison::Greater,
+                    ('>', true) => Comparison::GreaterOrEqual,
+                    ('=', _) => Comparison::Equal,
+                    ('!', true) => Comparison::NotEqual,
+                    _ => {
+                        return Err(ParseError::new(
+                            String::from("expected `=` after `!`"),
+                            column + 1,
+                        ))
+                    }
+                };
+                tokens.push((Token::Compare(comparison), column));
+            }
+            c if c.is_whitespace() => (),
+            c if is_name_char(c) => {
+                let mut word = c.to_string();
+
+                while let Some(&(_, c)) = chars.peek() {
+                    if !is_name_char(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+
+                let token = match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Name(word),
+                };
+                tokens.push((token, column));
+            }
+            c => {
+                return Err(ParseError::new(
+                    format!("unexpected character `{}`", c),
+                    column,
+                ))
+            }
+        }
+    }
+
+    tokens.push((Token::End, source.chars().count() + 1));
+    Ok(tokens)
+}
+
+// Numbers are read as names, so include their characters.
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'
+}
+
+/// Returns the entry in the list for the name, treating underscores as
+/// hyphens.
+fn find_name<T: Copy>(list: &[(&str, T)], name: &str) -> Option<T> {
+    let name = name.replace('_', "-");
+    list.iter()
+        .find(|(list_name, _)| *list_name == name)
+        .map(|(_, item)| *item)
+}
+
+/// A recursive descent parser over a list of tokens.
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    names: &'a Names<'a>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    /// Returns the next token and its column, staying on `Token::End` once it
+    /// is reached.
+    fn next(&mut self) -> (Token, usize) {
+        let token = self.tokens[self.position].clone();
+        if token.0 != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn or_expression(&mut self) -> Result<Expression, ParseError> {
+        let mut expression = self.and_expression()?;
+
+        while *self.peek() == Token::Or {
+            self.next();
+            expression = Expression::Or(Box::new(expression), Box::new(self.and_expression()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn and_expression(&mut self) -> Result<Expression, ParseError> {
+        let mut expression = self.not_expression()?;
+
+        while *self.peek() == Token::And {
+            self.next();
+            expression = Expression::And(Box::new(expression), Box::new(self.not_expression()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn not_expression(&mut self) -> Result<Expression, ParseError> {
+        if *self.peek() == Token::Not {
+            self.next();
+            Ok(Expression::Not(Box::new(self.not_expression()?)))
+        } else {
+            self.primary_expression()
+        }
+    }
+
+    fn primary_expression(&mut self) -> Result<Expression, ParseError> {
+        match self.next() {
+            (Token::LeftParenthesis, column) => {
+                let expression = self.or_expression()?;
+
+                match self.next() {
+                    (Token::RightParenthesis, _) => Ok(expression),
+                    (Token::End, _) => Err(ParseError::new(String::from("unclosed `(`"), column)),
+                    (token, column) => Err(ParseError::new(
+                        format!("expected `)` but found {}", token),
+                        column,
+                    )),
+                }
+            }
+            (Token::Name(name), column) => {
+                if let Some(flag) = find_name(self.names.statuses, &name) {
+                    Ok(Expression::Status(flag))
+                } else if let Some(value) = find_name(self.names.values, &name) {
+                    self.comparison(value)
+                } else {
+                    Err(ParseError::new(
+                        format!("unknown status `{}`", name),
+                        column,
+                    ))
+                }
+            }
+            (token, column) => Err(ParseError::new(
+                format!("expected a status name or `(` but found {}", token),
+                column,
+            )),
+        }
+    }
+
+    /// Returns the comparison of the given value that follows its name.
+    fn comparison(&mut self, value: Value) -> Result<Expression, ParseError> {
+        let comparison = match self.next() {
+            (Token::Compare(comparison), _) => comparison,
+            (token, column) => {
+                return Err(ParseError::new(
+                    format!("expected a comparison but found {}", token),
+                    column,
+                ))
+            }
+        };
+
+        match self.next() {
+            (Token::Name(name), column) => {
+                match name
+                    .parse()
+                    .ok()
+                    .or_else(|| find_name(self.names.constants, &name))
+                {
+                    Some(number) => Ok(Expression::Compare(value, comparison, number)),
+                    None => Err(ParseError::new(
+                        format!("expected a number but found `{}`", name),
+                        column,
+                    )),
+                }
+            }
+            (token, column) => Err(ParseError::new(
+                format!("expected a number but found {}", token),
+                column,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: StatusBitField = 1;
+    const B: StatusBitField = 2;
+    const C: StatusBitField = 4;
+    const NAMES: Names = Names {
+        statuses: &[("alpha", A), ("beta-two", B), ("gamma", C)],
+        values: &[("fuel", Value::Fuel), ("cargo", Value::Cargo)],
+        constants: &[("full", 100.0)],
+    };
+
+    fn parse(source: &str) -> Result<Expression, ParseError> {
+        Expression::parse(source, &NAMES)
+    }
+
+    fn no_values(_: Value) -> Option<f64> {
+        None
+    }
+
+    #[test]
+    fn parse_follows_operator_precedence() {
+        assert_eq!(
+            parse("alpha or not beta-two and gamma").unwrap(),
+            Expression::Or(
+                Box::new(Expression::Status(A)),
+                Box::new(Expression::And(
+                    Box::new(Expression::Not(Box::new(Expression::Status(B)))),
+                    Box::new(Expression::Status(C)),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn parse_groups_with_parentheses_and_accepts_underscores() {
+        let expression = parse("(alpha or beta_two) and not gamma").unwrap();
+
+        assert!(expression.evaluate(A, &no_values));
+        assert!(expression.evaluate(B, &no_values));
+        assert!(!expression.evaluate(A | C, &no_values));
+        assert!(!expression.evaluate(0, &no_values));
+    }
+
+    #[test]
+    fn parse_compares_values_with_numbers_and_constants() {
+        let expression = parse("fuel<25 or (alpha and cargo != 0.5) or fuel >= full").unwrap();
+        let values = |fuel, cargo| {
+            move |value| match value {
+                Value::Fuel => fuel,
+                Value::Cargo => cargo,
+                _ => None,
+            }
+        };
+
+        assert!(expression.evaluate(0, &values(Some(24.9), None)));
+        assert!(!expression.evaluate(0, &values(Some(25.0), None)));
+        assert!(expression.evaluate(0, &values(Some(100.0), None)));
+        assert!(expression.evaluate(A, &values(None, Some(1.0))));
+        assert!(!expression.evaluate(A, &values(None, Some(0.5))));
+        assert!(!expression.evaluate(A, &no_values));
+    }
+
+    #[test]
+    fn parse_reports_column_of_errors() {
+        let error_message = |source| parse(source).unwrap_err().to_string();
+
+        assert_eq!(
+            error_message("alpha and delta"),
+            "unknown status `delta` at column 11"
+        );
+        assert_eq!(
+            error_message("alpha & gamma"),
+            "unexpected character `&` at column 7"
+        );
+        assert_eq!(
+            error_message("alpha and"),
+            "expected a status name or `(` but found end of expression at column 10"
+        );
+        assert_eq!(error_message("not (alpha"), "unclosed `(` at column 5");
+        assert_eq!(
+            error_message("(alpha gamma)"),
+            "expected `)` but found `gamma` at column 8"
+        );
+        assert_eq!(
+            error_message("alpha gamma"),
+            "unexpected `gamma` after the end of the expression at column 7"
+        );
+        assert_eq!(
+            error_message("fuel alpha"),
+            "expected a comparison but found `alpha` at column 6"
+        );
+        assert_eq!(
+            error_message("fuel < lots"),
+            "expected a number but found `lots` at column 8"
+        );
+        assert_eq!(
+            error_message("fuel ! 5"),
+            "expected `=` after `!` at column 7"
+        );
+        assert_eq!(
+            error_message(""),
+            "expected a status name or `(` but found end of expression at column 1"
+        );
+    }
+}
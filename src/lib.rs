@@ -1,37 +1,175 @@
 pub mod config;
+mod display;
 mod events;
 mod game;
 mod x52pro;
 
 use config::Config;
+use display::Display;
 use events::Event;
-use game::{file::journal, file::journal::JournalReader, file::Status};
-use game::{Attribute, Control, Controls, Ship};
+use game::{
+    file::journal, file::journal::JournalReader, file::session, file::session::SessionRecorder,
+    file::Status,
+};
+use game::{Attribute, Control, ControlSet, Controls, Ship, ShipSettings};
 use hotwatch::Hotwatch;
 use log::{debug, info};
-use std::sync::mpsc;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
-use std::time::Duration;
-use x52pro::{Device, StatusLevelToModeMapper};
+use std::time::{Duration, Instant, SystemTime};
+use x52pro::{
+    device::{Input, LightMode},
+    Device, DeviceEvent, LedBackend, NativeDevices, SoftButton, StatusLevelToModeMapper,
+    TerminalLedBackend,
+};
+
+pub use x52pro::end_terminal_lights;
 
 const ANIMATION_TICK_MILLISECONDS: u64 = x52pro::ALERT_FLASH_MILLISECONDS as u64;
 
-pub fn run(config: Config) {
-    let mut x52pro = Device::new();
+// How often to look for the joystick while it's disconnected, for backends
+// that don't raise an event when it's plugged in.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Options, typically given on the command line, that change how the app runs.
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Render the lights in the terminal instead of on the device.
+    pub preview: bool,
+    /// Replay a recorded session instead of following the game.
+    pub replay: Option<ReplayOptions>,
+    /// Record the session to the given file so it can be replayed later.
+    pub record_file_path: Option<PathBuf>,
+}
+
+/// Options for replaying a recorded session.
+#[derive(Debug)]
+pub struct ReplayOptions {
+    /// The session file to replay.
+    pub file_path: PathBuf,
+    /// The speed multiplier, e.g. 2.0 replays twice as fast as recorded.
+    pub speed: f64,
+}
+
+/// Runs the app, controlling the lights on the configured X52 Pro devices or,
+/// if previewing, rendering the lights of each to the terminal.
+pub fn run(config: Config, options: Options) {
+    let devices = config.devices();
 
+    if options.preview {
+        let x52pros = TerminalLedBackend::for_devices(devices.len())
+            .into_iter()
+            .zip(devices)
+            .map(|(led_backend, device)| {
+                (
+                    Device::with_led_backend(led_backend),
+                    device.bindings_device,
+                )
+            })
+            .collect();
+        run_with_devices(config, options, x52pros);
+    } else {
+        // The devices share one instance of the native library, which is
+        // released once they've all been dropped.
+        let native_devices = NativeDevices::load();
+        let x52pros = devices
+            .into_iter()
+            .map(|device| {
+                let x52pro = Device::new(&native_devices, device.index, device.serial);
+                (x52pro, device.bindings_device)
+            })
+            .collect();
+        run_with_devices(config, options, x52pros);
+    }
+}
+
+/// Runs the app with the given devices, each with the name of its device in
+/// the bindings file.
+fn run_with_devices<T: LedBackend>(
+    config: Config,
+    options: Options,
+    x52pros: Vec<(Device<T>, String)>,
+) {
     let bindings_file_path = config.bindings_file_path();
     debug!("Bindings file path: {:?}", bindings_file_path);
 
-    let controls = Controls::from_file(&bindings_file_path);
-    debug!("Controls: {:?}", controls);
+    let mut devices: Vec<(Device<T>, Controls)> = x52pros
+        .into_iter()
+        .map(|(x52pro, bindings_device)| {
+            let controls = Controls::from_file(&bindings_file_path, &bindings_device);
+            debug!("Controls for {}: {:?}", bindings_device, controls);
+            (x52pro, controls)
+        })
+        .collect();
 
-    let status_file_path = game::file::status_file_path();
-    debug!("Status file path: {:?}", status_file_path);
+    let recorder = options.record_file_path.map(|record_file_path| {
+        info!("Recording session to file {:?}", record_file_path);
+        SessionRecorder::create(record_file_path)
+    });
 
-    let mut ship = Ship::new();
     let (tx, rx) = mpsc::channel();
 
-    let mut journal_reader = JournalReader::new();
+    // The watcher stops watching and the replay journal is deleted when
+    // dropped so both must live as long as the event loop.
+    let (_hotwatch, _replay_journal) = match options.replay {
+        Some(replay) => {
+            info!("Replaying session file {:?}", replay.file_path);
+            let replay_journal = session::replay(replay.file_path, replay.speed, &tx);
+            (None, Some(replay_journal))
+        }
+        None => (Some(watch_game_files(&tx)), None),
+    };
+
+    // The callbacks must be registered before the pages are added to hear
+    // about them being activated.
+    for (index, (x52pro, _)) in devices.iter_mut().enumerate() {
+        let tx = tx.clone();
+        x52pro.register_callback(Box::new(move |device_event| {
+            let event = match device_event {
+                DeviceEvent::Connected => Event::DeviceConnected,
+                DeviceEvent::PageActivated(page) => Event::MfdPageActivated(index, page),
+                DeviceEvent::SoftButton(soft_button) => Event::MfdSoftButton(index, soft_button),
+            };
+            // The backend can still call this from its own thread once the
+            // event loop has exited, where panicking would abort the process.
+            if tx.send(event).is_err() {
+                debug!("Device event {:?} ignored after exit", device_event);
+            }
+        }));
+        x52pro.add_mfd_pages(config.mfd_pages().len() as u32);
+    }
+
+    if config.timers_input().is_some() {
+        info!("There's no frame shift drive cooldown timer as the journal doesn't give its length");
+    }
+
+    let tx2 = tx.clone();
+    let tx3 = tx;
+
+    info!("Press Ctrl+C to exit");
+    ctrlc::set_handler(move || {
+        info!("Received Ctrl+C");
+        tx2.send(Event::Exit).expect("Could not send exit message");
+    })
+    .expect("Failed to set Ctrl+C handler");
+
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(ANIMATION_TICK_MILLISECONDS));
+        tx3.send(Event::AnimationTick)
+            .expect("Could not send animation tick message");
+    });
+
+    handle_events(rx, &mut devices, &config, recorder);
+
+    info!("Exiting");
+}
+
+/// Sends events for the current state of the game files then watches the
+/// files, sending further events as they change. Returns the watcher.
+fn watch_game_files(tx: &Sender<Event>) -> Hotwatch {
+    let status_file_path = game::file::status_file_path();
+    debug!("Status file path: {:?}", status_file_path);
 
     // Need to send this event before status so journal is read too.
     if let Some(journal_file_path) = game::file::latest_journal_file_path() {
@@ -46,8 +184,7 @@ pub fn run(config: Config) {
     tx.send(Event::StatusUpdate(initial_status))
         .expect("Could not send status update message");
 
-    let tx2 = tx.clone();
-    let tx3 = tx.clone();
+    let tx = tx.clone();
     let mut hotwatch = Hotwatch::new_with_custom_delay(Duration::from_millis(100))
         .expect("File watcher failed to initialize");
 
@@ -66,31 +203,103 @@ pub fn run(config: Config) {
         })
         .expect("Failed to watch status file");
 
-    info!("Press Ctrl+C to exit");
-    ctrlc::set_handler(move || {
-        info!("Received Ctrl+C");
-        tx2.send(Event::Exit).expect("Could not send exit message");
-    })
-    .expect("Failed to set Ctrl+C handler");
+    hotwatch
+}
 
-    thread::spawn(move || loop {
-        thread::sleep(Duration::from_millis(ANIMATION_TICK_MILLISECONDS));
-        tx3.send(Event::AnimationTick)
-            .expect("Could not send animation tick message");
-    });
+/// Handles each event received until an `Exit` event is received, updating the
+/// lights on the given devices, each with its own controls, as the ship's
+/// status changes. What is read from the game's files is recorded if given a
+/// recorder.
+fn handle_events<T: LedBackend>(
+    rx: Receiver<Event>,
+    devices: &mut [(Device<T>, Controls)],
+    config: &Config,
+    mut recorder: Option<SessionRecorder>,
+) {
+    let mut ship = Ship::with_rules(
+        config.rules(),
+        ShipSettings {
+            low_fuel_fraction: config.low_fuel_fraction(),
+            alert_duration: config.alert_duration(),
+            timer_warning: config.timer_warning(),
+        },
+    );
+    let mut journal_reader = JournalReader::new();
+    let display = Display::new(config.mfd_pages());
+    let mut reconnect_time = Instant::now() + RECONNECT_INTERVAL;
 
     for event in rx {
         match event {
             Event::NewJournalFile(file_path) => journal_reader.open(file_path),
             Event::Exit => break,
-            Event::AnimationTick => x52pro.update_animated_lights(),
+            // The lights are kept while a device is disconnected and
+            // rewritten on reconnection. The event doesn't say which device
+            // was plugged in so each disconnected one is tried.
+            Event::DeviceConnected => {
+                for (x52pro, _) in devices.iter_mut() {
+                    if !x52pro.is_connected() && x52pro.reconnect() {
+                        x52pro.set_mfd_lines(display.lines(x52pro.mfd_page(), &ship));
+                    }
+                }
+            }
+            Event::MfdPageActivated(index, page) => {
+                let (x52pro, _) = &mut devices[index];
+                x52pro.activate_page(page);
+                x52pro.set_mfd_lines(display.lines(x52pro.mfd_page(), &ship));
+            }
+            // The scroll wheel on the throttle also switches between pages.
+            Event::MfdSoftButton(index, soft_button) => {
+                if soft_button != SoftButton::Select {
+                    let (x52pro, _) = &mut devices[index];
+                    x52pro.scroll_mfd_page(soft_button == SoftButton::Down);
+                    x52pro.set_mfd_lines(display.lines(x52pro.mfd_page(), &ship));
+                }
+            }
+            Event::AnimationTick => {
+                let now = Instant::now();
+                let reconnect = now >= reconnect_time;
+                if reconnect {
+                    reconnect_time = now + RECONNECT_INTERVAL;
+                }
+                let time_changed = ship.update_time(now);
+
+                // The clock and timers change with time alone. Only changes
+                // are written to the devices so these are cheap to repeat.
+                let (hour, minute) = display::time_of_day(SystemTime::now());
+
+                for (x52pro, controls) in devices.iter_mut() {
+                    if reconnect && !x52pro.is_connected() {
+                        x52pro.reconnect();
+                    }
+
+                    if time_changed {
+                        set_x52pro_inputs_from_ship(x52pro, controls, config, &ship);
+                    } else {
+                        x52pro.update_animated_lights();
+                    }
+
+                    x52pro.set_clock(hour, minute);
+                    x52pro.set_mfd_lines(display.lines(x52pro.mfd_page(), &ship));
+                }
+            }
             Event::StatusUpdate(status) => {
                 // Unlike the status file, it appears that the current journal
                 // file is kept open by the game, which in turn appears to
                 // prevent write events being raised immediately on the file,
                 // meaning we can't watch it for changes. Instead, we try
                 // reading each time the status file is re-written.
-                let journal_events = journal_reader.new_events();
+                let journal_lines = journal_reader.new_lines();
+
+                // Record the journal lines first so that a replay appends them
+                // to the journal before sending the status.
+                if let Some(recorder) = &mut recorder {
+                    for line in &journal_lines {
+                        recorder.record_journal_line(line);
+                    }
+                    recorder.record_status(&status);
+                }
+
+                let journal_events = journal::events_from_lines(&journal_lines);
                 let journal_events_present = !journal_events.is_empty();
 
                 for journal_event in journal_events {
@@ -101,33 +310,88 @@ pub fn run(config: Config) {
                 // even pass in the reader itself, although that's increasing
                 // the coupling.
                 if ship.update_status(status) | journal_events_present {
-                    set_x52pro_inputs_from_ship_statues(
-                        &mut x52pro,
-                        &controls,
-                        ship.statuses(),
-                        &config.status_level_to_mode_mapper(ship.global_status()),
-                    );
+                    for (x52pro, controls) in devices.iter_mut() {
+                        set_x52pro_inputs_from_ship(x52pro, controls, config, &ship);
+                    }
                 } else {
                     debug!("Status file updated but change not relevant");
                 }
+
+                // Only changed lines are written so values like fuel can be
+                // shown as they change without rewriting the lights.
+                for (x52pro, _) in devices.iter_mut() {
+                    x52pro.set_mfd_lines(display.lines(x52pro.mfd_page(), &ship));
+                }
             }
         }
     }
+}
 
-    info!("Exiting");
+/// Sets the lights on the device from the current state of the ship.
+fn set_x52pro_inputs_from_ship<T: LedBackend>(
+    x52pro: &mut Device<T>,
+    controls: &Controls,
+    config: &Config,
+    ship: &Ship,
+) {
+    // Pips and fire groups are only shown when in the ship.
+    let mut input_light_modes = Vec::new();
+
+    if ship.control_set() == ControlSet::Ship {
+        if let Some(fire_group) = ship.fire_group() {
+            input_light_modes.extend(config.fire_group_input_light_modes(fire_group));
+        }
+        if let Some(pips) = ship.pips() {
+            input_light_modes.extend(config.pips_input_light_modes(pips));
+        }
+    }
+
+    // Attributes with no game control are shown on the configured input
+    // instead, the fuel scoop only when in the ship.
+    let mut attribute_inputs = Vec::new();
+
+    if let (Some(input), ControlSet::Ship) = (config.fuel_scoop_input(), ship.control_set()) {
+        attribute_inputs.push((Attribute::FuelScoop, input));
+    }
+    if let Some(input) = config.timers_input() {
+        attribute_inputs.push((Attribute::Timers, input));
+    }
+
+    set_x52pro_inputs_from_ship_statues(
+        x52pro,
+        controls,
+        attribute_inputs,
+        ship.statuses(),
+        ship.control_set(),
+        input_light_modes,
+        &config.status_level_to_mode_mapper(ship.global_status()),
+    );
 }
 
-fn set_x52pro_inputs_from_ship_statues(
-    x52pro: &mut Device,
+fn set_x52pro_inputs_from_ship_statues<T: LedBackend>(
+    x52pro: &mut Device<T>,
     controls: &Controls,
+    attribute_inputs: Vec<(Attribute, Input)>,
     statuses: Vec<game::Status>,
+    control_set: ControlSet,
+    input_light_modes: Vec<(Input, LightMode)>,
     status_level_to_mode_mapper: &StatusLevelToModeMapper,
 ) {
     fn controls_for_status(status: &game::Status) -> Vec<Control> {
         match status.attribute {
             Attribute::Boost => vec![Control::Boost],
             Attribute::CargoScoop => vec![Control::CargoScoop],
-            Attribute::ExternalLights => vec![Control::ExternalLights],
+            Attribute::EnginesPower => vec![Control::IncreaseEnginesPower],
+            Attribute::ExternalLights => {
+                vec![
+                    Control::ExternalLights,
+                    Control::HumanoidFlashlight,
+                    Control::SrvHeadlights,
+                ]
+            }
+            Attribute::FireGroups => {
+                vec![Control::CycleFireGroupNext, Control::CycleFireGroupPrevious]
+            }
             Attribute::FrameShiftDrive => vec![
                 Control::Hyperspace,
                 Control::HyperSuperCombination,
@@ -135,24 +399,398 @@ fn set_x52pro_inputs_from_ship_statues(
             ],
             Attribute::Hardpoints => vec![Control::Hardpoints],
             Attribute::HeatSink => vec![Control::HeatSink],
+            Attribute::HudMode => vec![Control::PlayerHudModeToggle],
             Attribute::LandingGear => vec![Control::LandingGear],
-            Attribute::NightVision => vec![Control::NightVision],
+            Attribute::Health => vec![Control::HumanoidHealthPack],
+            Attribute::NightVision => vec![Control::NightVision, Control::HumanoidNightVision],
+            Attribute::PowerDistribution => vec![Control::ResetPowerDistribution],
             Attribute::SilentRunning => vec![Control::SilentRunning],
+            Attribute::SrvDriveAssist => vec![Control::SrvDriveAssist],
+            Attribute::SrvHandbrake => vec![Control::SrvHandbrake],
+            Attribute::SrvShip => vec![Control::SrvRecallDismissShip],
+            Attribute::SrvTurret => vec![Control::SrvTurret],
+            Attribute::SuitEquipment => {
+                vec![Control::HumanoidPrimaryFire, Control::HumanoidRechargeTool]
+            }
+            Attribute::SystemsPower => vec![Control::IncreaseSystemsPower],
             Attribute::Throttle => vec![Control::Throttle],
+            Attribute::UiNavigation => vec![
+                Control::CycleNextPanel,
+                Control::CyclePreviousPanel,
+                Control::UiBack,
+                Control::UiDown,
+                Control::UiLeft,
+                Control::UiRight,
+                Control::UiSelect,
+                Control::UiUp,
+            ],
+            Attribute::WeaponsPower => vec![Control::IncreaseWeaponsPower],
+            // Attributes with no corresponding game controls.
+            Attribute::FuelScoop
+            | Attribute::Glide
+            | Attribute::Oxygen
+            | Attribute::Temperature
+            | Attribute::Timers => vec![],
         }
     }
 
     let mut input_status_levels = Vec::new();
 
-    // This can probably be written functionally by mapping.
+    // This can probably be written functionally by mapping. Only controls in
+    // the set currently in use are lit so that, for example, ship statuses
+    // are not shown when on foot.
     for status in statuses {
-        for control in controls_for_status(&status) {
+        for control in controls_for_status(&status)
+            .into_iter()
+            .filter(|control| control.in_control_set(control_set))
+        {
             for input in controls.inputs_for_control(control) {
                 debug!("Input={:?}, StatusLevel={:?}", input, status.level);
-                input_status_levels.push((input, status.level.clone()));
+                input_status_levels.push((input, status.level));
             }
         }
+
+        for (_, input) in attribute_inputs
+            .iter()
+            .filter(|(attribute, _)| *attribute == status.attribute)
+        {
+            debug!("Input={:?}, StatusLevel={:?}", input, status.level);
+            input_status_levels.push((*input, status.level));
+        }
+    }
+
+    x52pro.set_input_status_levels(
+        input_status_levels,
+        input_light_modes,
+        status_level_to_mode_mapper,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::X52PRO_DEVICE;
+    use x52pro::{device::Led, RecordingLedBackend};
+
+    fn fixture_path(file_name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(file_name)
+    }
+
+    fn recording_device() -> (Device<RecordingLedBackend>, Controls) {
+        let controls = Controls::from_file(&fixture_path("Custom.binds"), X52PRO_DEVICE);
+        (
+            Device::with_led_backend(RecordingLedBackend::new()),
+            controls,
+        )
+    }
+
+    fn fixture_config() -> Config {
+        Config::from_file(fixture_path("edxlc.toml").to_string_lossy().into())
+    }
+
+    fn fixture_status() -> Status {
+        Status::from_file(&fixture_path("Status.json")).unwrap()
+    }
+
+    /// Handles the given events, then exits, with the fixture config on the
+    /// given devices.
+    fn handle_events_on(
+        devices: &mut [(Device<RecordingLedBackend>, Controls)],
+        events: Vec<Event>,
+    ) {
+        let (tx, rx) = mpsc::channel();
+        for event in events {
+            tx.send(event).unwrap();
+        }
+        tx.send(Event::Exit).unwrap();
+
+        handle_events(rx, devices, &fixture_config(), None);
+    }
+
+    /// Handles the given events, then exits, on a new device and returns it.
+    fn run_events(events: Vec<Event>) -> Device<RecordingLedBackend> {
+        let mut devices = vec![recording_device()];
+        handle_events_on(&mut devices, events);
+        devices.remove(0).0
+    }
+
+    fn assert_leds(backend: &RecordingLedBackend, expected_led_states: Vec<(Led, bool)>) {
+        for (led, expected_state) in expected_led_states {
+            assert_eq!(
+                backend.led_state(led),
+                Some(expected_state),
+                "Unexpected state for {:?}",
+                led
+            );
+        }
+    }
+
+    #[test]
+    fn handle_events_sets_lights_from_fixture_files() {
+        let x52pro = run_events(vec![
+            Event::NewJournalFile(fixture_path("Journal.log")),
+            Event::StatusUpdate(fixture_status()),
+        ]);
+
+        let backend = x52pro.led_backend();
+
+        // Landing gear is bound to T1 and deployed so T1T2 shows amber.
+        assert_leds(backend, vec![(Led::T1T2Red, true), (Led::T1T2Green, true)]);
+        // Cargo scoop is bound to T3 and is not deployed so T3T4 shows green.
+        assert_leds(backend, vec![(Led::T3T4Red, false), (Led::T3T4Green, true)]);
+        // Boost (FireA) and FSD (T5) are blocked by the landing gear so show red.
+        assert_leds(
+            backend,
+            vec![(Led::FireARed, true), (Led::FireAGreen, false)],
+        );
+        assert_leds(backend, vec![(Led::T5T6Red, true), (Led::T5T6Green, false)]);
+        // Boolean lights are on when inactive.
+        assert_leds(backend, vec![(Led::Fire, true), (Led::Throttle, true)]);
+        // The docking message replaces the last line of the MFD.
+        assert_eq!(backend.mfd_line(0), Some(String::from("-")));
+        assert_eq!(backend.mfd_line(2), Some(String::from("Docking: pad 14")));
+    }
+
+    #[test]
+    fn handle_events_rewrites_lights_when_device_reconnected() {
+        let mut devices = vec![recording_device()];
+
+        // Unplugged then plugged back in but not yet enumerated again.
+        devices[0].0.led_backend().set_plugged_in(false);
+        devices[0].0.led_backend().set_plugged_in(true);
+
+        handle_events_on(
+            &mut devices,
+            vec![
+                Event::NewJournalFile(fixture_path("Journal.log")),
+                Event::StatusUpdate(fixture_status()),
+                Event::DeviceConnected,
+            ],
+        );
+
+        let x52pro = &devices[0].0;
+
+        let backend = x52pro.led_backend();
+        assert!(x52pro.is_connected());
+        assert_eq!(backend.timeline().len(), 20);
+        assert_leds(backend, vec![(Led::T1T2Red, true), (Led::T1T2Green, true)]);
+        assert_eq!(backend.mfd_line(2), Some(String::from("Docking: pad 14")));
+    }
+
+    #[test]
+    fn handle_events_switches_mfd_pages() {
+        let mut devices = vec![recording_device()];
+        devices[0].0.add_mfd_pages(3);
+
+        handle_events_on(
+            &mut devices,
+            vec![
+                Event::StatusUpdate(fixture_status()),
+                Event::MfdPageActivated(0, 2),
+            ],
+        );
+
+        let x52pro = &devices[0].0;
+
+        assert_eq!(x52pro.mfd_page(), 2);
+        assert_eq!(
+            x52pro.led_backend().mfd_line(0),
+            Some(String::from("Fire group -"))
+        );
+    }
+
+    #[test]
+    fn handle_events_scrolls_mfd_pages() {
+        let mut devices = vec![recording_device()];
+        devices[0].0.add_mfd_pages(3);
+
+        handle_events_on(
+            &mut devices,
+            vec![
+                Event::StatusUpdate(fixture_status()),
+                Event::MfdSoftButton(0, SoftButton::Up),
+                Event::MfdSoftButton(0, SoftButton::Select),
+                Event::MfdSoftButton(0, SoftButton::Down),
+                Event::MfdSoftButton(0, SoftButton::Down),
+            ],
+        );
+
+        let x52pro = &devices[0].0;
+
+        assert_eq!(x52pro.mfd_page(), 1);
+        assert_eq!(
+            x52pro.led_backend().mfd_line(0),
+            Some(String::from("Cargo -t"))
+        );
+    }
+
+    #[test]
+    fn handle_events_shows_page_scrolled_to_once_and_rewrites_its_text() {
+        let mut devices = vec![recording_device()];
+        devices[0].0.add_mfd_pages(3);
+
+        // DirectOutput reports the page it's asked to show as activated,
+        // which mustn't show it again.
+        handle_events_on(
+            &mut devices,
+            vec![
+                Event::StatusUpdate(fixture_status()),
+                Event::MfdSoftButton(0, SoftButton::Down),
+                Event::MfdPageActivated(0, 1),
+            ],
+        );
+
+        let x52pro = &devices[0].0;
+
+        assert_eq!(x52pro.led_backend().shown_pages(), vec![1]);
+        assert_eq!(x52pro.mfd_page(), 1);
+        assert_eq!(
+            x52pro.led_backend().mfd_line(0),
+            Some(String::from("Cargo -t"))
+        );
+    }
+
+    #[test]
+    fn handle_events_sets_lights_and_pages_on_each_device() {
+        let mut devices = vec![recording_device(), recording_device()];
+        for (x52pro, _) in devices.iter_mut() {
+            x52pro.add_mfd_pages(3);
+        }
+
+        handle_events_on(
+            &mut devices,
+            vec![
+                Event::StatusUpdate(fixture_status()),
+                Event::MfdPageActivated(1, 2),
+            ],
+        );
+
+        // Only the second device switched page but both show the lights.
+        assert_eq!(devices[0].0.mfd_page(), 0);
+        assert_eq!(devices[1].0.mfd_page(), 2);
+        for (x52pro, _) in &devices {
+            assert_leds(
+                x52pro.led_backend(),
+                vec![(Led::T1T2Red, true), (Led::T1T2Green, true)],
+            );
+        }
     }
 
-    x52pro.set_input_status_levels(input_status_levels, status_level_to_mode_mapper);
+    #[test]
+    fn handle_events_only_writes_lights_on_relevant_changes() {
+        let x52pro = run_events(vec![
+            Event::StatusUpdate(fixture_status()),
+            Event::StatusUpdate(fixture_status()),
+        ]);
+
+        // One write for each of the 20 LEDs, none for the repeated status.
+        assert_eq!(x52pro.led_backend().timeline().len(), 20);
+    }
+
+    #[test]
+    fn handle_events_sets_lights_from_replayed_session() {
+        let mut devices = vec![recording_device()];
+
+        let (tx, rx) = mpsc::channel();
+        let _replay_journal = session::replay(fixture_path("session.jsonl"), 100.0, &tx);
+
+        handle_events(rx, &mut devices, &fixture_config(), None);
+
+        let x52pro = &devices[0].0;
+
+        let backend = x52pro.led_backend();
+        let landing_gear_states: Vec<bool> = backend
+            .timeline()
+            .iter()
+            .filter(|write| write.led_id == Led::T1T2Red as u32)
+            .map(|write| write.active)
+            .collect();
+
+        // Landing gear bound to T1 goes from inactive (green) to alert when
+        // docking is granted (amber/off) then active (amber) when deployed.
+        assert_eq!(landing_gear_states.first(), Some(&false));
+        assert_eq!(landing_gear_states.last(), Some(&true));
+        assert_leds(backend, vec![(Led::T1T2Red, true), (Led::T1T2Green, true)]);
+    }
+
+    #[test]
+    fn handle_events_does_not_show_ship_statuses_when_on_foot() {
+        let x52pro = run_events(vec![Event::StatusUpdate(Status::from_json(String::from(
+            r#"{ "event":"Status", "Flags":4, "Flags2":1 }"#,
+        )))]);
+
+        // Landing gear bound to T1 is deployed but shows inactive (green).
+        assert_leds(
+            x52pro.led_backend(),
+            vec![(Led::T1T2Red, false), (Led::T1T2Green, true)],
+        );
+    }
+
+    #[test]
+    fn handle_events_shows_srv_statuses_when_in_srv() {
+        let x52pro = run_events(vec![Event::StatusUpdate(Status::from_json(String::from(
+            r#"{ "event":"Status", "Flags":67112960 }"#,
+        )))]);
+
+        // Handbrake bound to T1 is applied and shows active (amber).
+        assert_leds(
+            x52pro.led_backend(),
+            vec![(Led::T1T2Red, true), (Led::T1T2Green, true)],
+        );
+    }
+
+    #[test]
+    fn handle_events_flashes_lights_for_timed_journal_statuses() {
+        let in_ship =
+            || Status::from_json(String::from(r#"{ "event":"Status", "Flags":16777216 }"#));
+        let journal_file_path = std::env::temp_dir().join(format!(
+            "edxlc-timed-status-test-{}.log",
+            std::process::id()
+        ));
+
+        for (journal_line, red_led, green_led) in [
+            (
+                r#"{ "event":"HeatSinkLaunched" }"#,
+                Led::FireERed,
+                Led::FireEGreen,
+            ),
+            (r#"{ "event":"Scan" }"#, Led::FireERed, Led::FireEGreen),
+            (
+                r#"{ "event":"ShieldState", "ShieldsUp":true }"#,
+                Led::PoV2Red,
+                Led::PoV2Green,
+            ),
+            (
+                r#"{ "event":"FSDJump", "StarSystem":"A" }"#,
+                Led::T5T6Red,
+                Led::T5T6Green,
+            ),
+        ] {
+            // The light shows inactive (green) without the event.
+            let x52pro = run_events(vec![Event::StatusUpdate(in_ship())]);
+            assert_leds(
+                x52pro.led_backend(),
+                vec![(red_led, false), (green_led, true)],
+            );
+
+            std::fs::write(&journal_file_path, journal_line).unwrap();
+            let x52pro = run_events(vec![
+                Event::NewJournalFile(journal_file_path.clone()),
+                Event::StatusUpdate(in_ship()),
+            ]);
+            std::fs::remove_file(&journal_file_path).unwrap();
+
+            // Flashing alert shows amber or off depending on the time.
+            let backend = x52pro.led_backend();
+            assert_eq!(
+                backend.led_state(red_led),
+                backend.led_state(green_led),
+                "Unexpected state after {}",
+                journal_line
+            );
+        }
+    }
 }
@@ -1,8 +1,16 @@
 use edxlc::config::Config;
+use edxlc::{Options, ReplayOptions};
 use log::{debug, info};
 use std::env;
+use std::io::Write;
+use std::path::PathBuf;
 
 const CONFIG_FILENAME: &str = "edxlc.toml";
+const PREVIEW_OPTION: &str = "--preview";
+const REPLAY_OPTION: &str = "--replay";
+const SPEED_OPTION: &str = "--speed";
+const RECORD_OPTION: &str = "--record";
+const DEFAULT_REPLAY_SPEED: f64 = 1.0;
 
 #[cfg(debug_assertions)]
 const DEFAULT_LOG_LEVEL: &str = "edxlc=debug";
@@ -12,34 +20,98 @@ const DEFAULT_LOG_LEVEL: &str = "info";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 fn main() {
-    configure_logger();
+    configure_logger(env::args().any(|arg| arg == PREVIEW_OPTION));
     info!("EDXLC {}", VERSION);
 
+    let (config_filename, options) = parse_args(env::args().skip(1));
+    debug!("{:?}", options);
+
     edxlc::config::write_default_file_if_missing(CONFIG_FILENAME);
-    let config = Config::from_file(config_filename());
+    let config = Config::from_file(config_filename);
     debug!("{:?}", config);
 
-    edxlc::run(config);
+    edxlc::run(config, options);
 }
 
-fn config_filename() -> String {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() < 2 {
-        let config_filename = String::from(CONFIG_FILENAME);
-        debug!("Using default configuration filename: {}", config_filename);
-        config_filename
-    } else {
-        let config_filename = &args[1];
-        debug!(
-            "Using command line configuration filename: {}",
-            config_filename
-        );
-        config_filename.clone()
+/// Returns the configuration filename and the options given by the command
+/// line arguments. The configuration filename is the first argument that is
+/// not an option, or the default filename if there is none. Panics if an
+/// option is missing its value.
+fn parse_args(mut args: impl Iterator<Item = String>) -> (String, Options) {
+    let mut config_filename = None;
+    let mut options = Options::default();
+    let mut replay_file_path = None;
+    let mut replay_speed = DEFAULT_REPLAY_SPEED;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            PREVIEW_OPTION => options.preview = true,
+            REPLAY_OPTION => {
+                let path = args.next().expect("Missing session file for --replay");
+                replay_file_path = Some(PathBuf::from(path));
+            }
+            RECORD_OPTION => {
+                let path = args.next().expect("Missing session file for --record");
+                options.record_file_path = Some(PathBuf::from(path));
+            }
+            SPEED_OPTION => {
+                replay_speed = args
+                    .next()
+                    .and_then(|speed| speed.parse().ok())
+                    .filter(|speed| *speed > 0.0)
+                    .expect("Missing or invalid positive number for --speed");
+            }
+            _ => {
+                if config_filename.is_none() {
+                    config_filename = Some(arg);
+                }
+            }
+        }
     }
+
+    options.replay = replay_file_path.map(|file_path| ReplayOptions {
+        file_path,
+        speed: replay_speed,
+    });
+
+    let config_filename = match config_filename {
+        Some(config_filename) => {
+            debug!(
+                "Using command line configuration filename: {}",
+                config_filename
+            );
+            config_filename
+        }
+        None => {
+            let config_filename = String::from(CONFIG_FILENAME);
+            debug!("Using default configuration filename: {}", config_filename);
+            config_filename
+        }
+    };
+
+    (config_filename, options)
 }
 
-fn configure_logger() {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(DEFAULT_LOG_LEVEL))
-        .init();
+/// Configures the logger. When previewing, each message first moves past the
+/// lights drawn in the terminal so it doesn't land in the middle of them.
+fn configure_logger(preview: bool) {
+    let mut builder = env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or(DEFAULT_LOG_LEVEL),
+    );
+
+    if preview {
+        builder.format(|buf, record| {
+            edxlc::end_terminal_lights();
+            writeln!(
+                buf,
+                "[{} {:<5} {}] {}",
+                buf.timestamp(),
+                buf.default_styled_level(record.level()),
+                record.module_path().unwrap_or_default(),
+                record.args()
+            )
+        });
+    }
+
+    builder.init();
 }
@@ -1,8 +1,36 @@
 pub mod device;
+#[cfg(any(windows, target_os = "linux"))]
+mod device_selector;
+#[cfg(windows)]
 pub mod direct_output;
+mod led_backend;
 mod light_mode_to_state_mapper;
+#[cfg(test)]
+mod recording_led_backend;
 mod status_level_to_mode_mapper;
+mod terminal_led_backend;
+#[cfg(target_os = "linux")]
+pub mod usb_control;
 
 pub use device::Device;
+#[cfg(any(windows, target_os = "linux"))]
+use device_selector::{ClaimedSerials, DeviceSelector};
+pub use led_backend::{DeviceCallback, DeviceEvent, LedBackend, SoftButton};
 pub use light_mode_to_state_mapper::{LightModeToStateMapper, ALERT_FLASH_MILLISECONDS};
+#[cfg(test)]
+pub use recording_led_backend::RecordingLedBackend;
 pub use status_level_to_mode_mapper::StatusLevelToModeMapper;
+pub use terminal_led_backend::{end_terminal_lights, TerminalLedBackend};
+
+/// The `LedBackend` that drives a physical device on the current platform.
+#[cfg(windows)]
+pub type NativeLedBackend = direct_output::DirectOutput;
+#[cfg(target_os = "linux")]
+pub type NativeLedBackend = usb_control::UsbControl;
+
+/// What the `NativeLedBackend` instances driving each device share, e.g. the
+/// loaded DirectOutput library.
+#[cfg(windows)]
+pub type NativeDevices = direct_output::DirectOutputLibrary;
+#[cfg(target_os = "linux")]
+pub type NativeDevices = usb_control::UsbDevices;
@@ -1,12 +1,17 @@
 use crate::game::StatusLevel;
-use crate::x52pro::{direct_output::DirectOutput, LightModeToStateMapper, StatusLevelToModeMapper};
+use crate::x52pro::{
+    DeviceCallback, LedBackend, LightModeToStateMapper, NativeDevices, NativeLedBackend,
+    StatusLevelToModeMapper,
+};
 use enum_iterator::IntoEnumIterator;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::rc::Rc;
 
 /// Controllable LEDs on the device. Assigned values correspond to the ids used
 /// by DirectOutput.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum Led {
     Fire = 0,
     FireARed = 1,
@@ -31,24 +36,48 @@ pub enum Led {
 }
 
 /// An instance of an interface to a Saitek X52 Pro Flight HOTAS flight
-/// controller device.
-pub struct Device {
-    direct_output: DirectOutput,
-    lights: HashMap<Light, Box<dyn LightMapping>>,
+/// controller device, driven through an `LedBackend` that defaults to the
+/// native backend for the platform, e.g. the DirectOutput library on Windows.
+pub struct Device<T: LedBackend = NativeLedBackend> {
+    led_backend: T,
+    lights: HashMap<Light, Box<dyn LightMapping<T>>>,
     animated_lights: Vec<Light>,
     light_mode_to_state_mapper: LightModeToStateMapper,
+    mfd_lines: [String; MFD_LINE_COUNT],
+    mfd_page: u32,
+    mfd_page_count: u32,
+    clock: Option<(u32, u32)>,
 }
 
+/// The number of lines on the MFD.
+pub const MFD_LINE_COUNT: usize = 3;
+
+// The number of characters on each line of the MFD.
+const MFD_LINE_LENGTH: usize = 16;
+
 impl Device {
-    /// Returns a new instance of the device interface. Panics if the
-    /// underlying `DirectOutput` instance cannot be loaded.
-    pub fn new() -> Self {
-        let mut direct_output = DirectOutput::load();
-        direct_output.initialize();
-        direct_output.enumerate();
-        direct_output.add_page();
+    /// Returns a new instance of the interface to the X52 Pro with the given
+    /// serial number, if any, or else the given number in the order they're
+    /// found, driven through the given shared native devices, e.g. the
+    /// DirectOutput library, which are loaded with `NativeDevices::load`.
+    pub fn new(devices: &Rc<NativeDevices>, index: usize, serial: Option<String>) -> Self {
+        Self::with_led_backend(NativeLedBackend::load(devices, index, serial))
+    }
+}
+
+impl<T: LedBackend> Device<T> {
+    /// Returns a new instance of the device interface that drives its LEDs
+    /// through the given backend, which is initialized here. The device need
+    /// not be connected yet, see `reconnect`.
+    pub fn with_led_backend(mut led_backend: T) -> Self {
+        led_backend.initialize();
+        if led_backend.enumerate() {
+            led_backend.add_page(0, true);
+        } else {
+            warn!("No X52 Pro connected");
+        }
 
-        let mut lights = HashMap::<Light, Box<dyn LightMapping>>::new();
+        let mut lights = HashMap::<Light, Box<dyn LightMapping<T>>>::new();
 
         lights.insert(
             Light::Clutch,
@@ -93,19 +122,26 @@ impl Device {
         );
 
         Device {
-            direct_output,
+            led_backend,
             lights,
             animated_lights: vec![],
             light_mode_to_state_mapper: LightModeToStateMapper::new(),
+            mfd_lines: Default::default(),
+            mfd_page: 0,
+            mfd_page_count: 1,
+            clock: None,
         }
     }
 
     /// Sets each input to specified status level. Repeated inputs with
     /// different status levels are handled by using the highest value. The Light
     /// for the input is looked up, as is the Light state for the status level.
+    /// Inputs given a light mode directly take that mode regardless of status
+    /// level.
     pub fn set_input_status_levels(
         &mut self,
         input_status_levels: Vec<(Input, StatusLevel)>,
+        input_light_modes: Vec<(Input, LightMode)>,
         status_level_to_mode_mapper: &StatusLevelToModeMapper,
     ) {
         // A hash mapping every light to the highest status level encountered.
@@ -125,24 +161,40 @@ impl Device {
             let light_status_level = light_highest_status_levels.get_mut(&light).unwrap();
 
             if status_level > *light_status_level {
-                *light_status_level = status_level.clone();
+                *light_status_level = status_level;
             }
         }
 
+        let light_modes: HashMap<Light, LightMode> = input_light_modes
+            .into_iter()
+            .map(|(input, light_mode)| (light_for_input(input), light_mode))
+            .collect();
+
         // Update the list of lights that are in a mode that requires animation.
+        // The modes are kept while disconnected to be written on reconnection.
         self.animated_lights.clear();
+        let connected = self.led_backend.is_connected();
 
         for (light, status_level) in &light_highest_status_levels {
-            let light_mode = status_level_to_mode_mapper.map(status_level);
+            let light_mode = match light_modes.get(light) {
+                Some(light_mode) => *light_mode,
+                None => status_level_to_mode_mapper.map(status_level),
+            };
             let light_mapping = self.lights.get_mut(light).expect("Can't find light");
 
             light_mapping.set_mode(light_mode);
-            light_mapping.update_state(&self.direct_output, &self.light_mode_to_state_mapper);
+            if connected {
+                light_mapping.update_state(&self.led_backend, &self.light_mode_to_state_mapper);
+            }
 
             if light_mapping.is_animated() {
                 self.animated_lights.push(*light);
             }
         }
+
+        if connected {
+            self.led_backend.flush();
+        }
     }
 
     /// Updates lights that have a state that is animated, e.g. flashing. This
@@ -151,15 +203,143 @@ impl Device {
     // Ideally the device would manage its own threading for animation but
     // this would require state updates to be communicated asynchronously.
     pub fn update_animated_lights(&self) {
+        if !self.led_backend.is_connected() {
+            return;
+        }
+
         for light in &self.animated_lights {
             let light_mapping = self.lights.get(light).expect("Can't find light");
-            light_mapping.update_state(&self.direct_output, &self.light_mode_to_state_mapper);
+            light_mapping.update_state(&self.led_backend, &self.light_mode_to_state_mapper);
+        }
+
+        self.led_backend.flush();
+    }
+
+    /// Registers the given function to be called with events raised by the
+    /// device, e.g. when the user switches MFD pages.
+    pub fn register_callback(&mut self, callback: DeviceCallback) {
+        self.led_backend.register_callback(callback);
+    }
+
+    /// Adds display pages so that there are the given number in total.
+    pub fn add_mfd_pages(&mut self, count: u32) {
+        if self.led_backend.is_connected() {
+            for page in self.mfd_page_count..count {
+                self.led_backend.add_page(page, false);
+            }
+        }
+        self.mfd_page_count = self.mfd_page_count.max(count);
+    }
+
+    /// Returns true if the device is connected. While disconnected the lights
+    /// and MFD lines are kept but not written.
+    pub fn is_connected(&self) -> bool {
+        self.led_backend.is_connected()
+    }
+
+    /// Enumerates the devices again, e.g. after the device was plugged back
+    /// in, returning true if it's connected. The pages are added again and all
+    /// the lights rewritten to the first page. The MFD lines must be set again
+    /// afterwards.
+    pub fn reconnect(&mut self) -> bool {
+        if !self.led_backend.enumerate() {
+            return false;
+        }
+
+        info!("X52 Pro connected");
+        for page in 0..self.mfd_page_count {
+            self.led_backend.add_page(page, page == 0);
+        }
+        self.clock = None;
+        self.activate_page(0);
+
+        true
+    }
+
+    /// Returns the index of the MFD page currently shown.
+    pub fn mfd_page(&self) -> u32 {
+        self.mfd_page
+    }
+
+    /// Makes the page with the given index, which the device has activated,
+    /// the page shown and rewrites all the lights to it. The MFD lines must be
+    /// set again afterwards.
+    pub fn activate_page(&mut self, page: u32) {
+        self.led_backend.set_page(page);
+        self.mfd_page = page;
+        self.mfd_lines = Default::default();
+
+        if !self.led_backend.is_connected() {
+            return;
+        }
+
+        for light_mapping in self.lights.values() {
+            light_mapping.update_state(&self.led_backend, &self.light_mode_to_state_mapper);
+        }
+
+        self.led_backend.flush();
+    }
+
+    /// Shows the next (or previous) page on the MFD, wrapping around, making
+    /// it the device's active page so the page shown and the page written to
+    /// stay the same. The MFD lines must be set again afterwards.
+    pub fn scroll_mfd_page(&mut self, forwards: bool) {
+        let page = if forwards {
+            (self.mfd_page + 1) % self.mfd_page_count
+        } else {
+            (self.mfd_page + self.mfd_page_count - 1) % self.mfd_page_count
+        };
+
+        if self.led_backend.is_connected() {
+            self.led_backend.show_page(page);
+        }
+        self.activate_page(page);
+    }
+
+    /// Shows the given lines of text on the MFD, truncated to fit, blanking
+    /// any lines not given. Only lines that have changed are written.
+    pub fn set_mfd_lines(&mut self, lines: Vec<String>) {
+        if !self.led_backend.is_connected() {
+            return;
+        }
+
+        let mut changed = false;
+
+        for (index, mfd_line) in self.mfd_lines.iter_mut().enumerate() {
+            let line: String = lines
+                .get(index)
+                .map(|line| line.chars().take(MFD_LINE_LENGTH).collect())
+                .unwrap_or_default();
+
+            if line != *mfd_line {
+                self.led_backend.set_string(index as u32, &line);
+                *mfd_line = line;
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.led_backend.flush();
         }
     }
+
+    /// Sets the clock to the given hour and minute if it has changed.
+    pub fn set_clock(&mut self, hour: u32, minute: u32) {
+        if self.led_backend.is_connected() && self.clock != Some((hour, minute)) {
+            self.led_backend.set_clock(hour, minute);
+            self.clock = Some((hour, minute));
+        }
+    }
+
+    #[cfg(test)]
+    pub fn led_backend(&self) -> &T {
+        &self.led_backend
+    }
 }
 
 /// Supported input buttons or axes on the device.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
 pub enum Input {
     Clutch,
     Fire,
@@ -167,9 +347,13 @@ pub enum Input {
     FireB,
     FireD,
     FireE,
+    #[serde(rename = "pov2-down")]
     PoV2Down,
+    #[serde(rename = "pov2-left")]
     PoV2Left,
+    #[serde(rename = "pov2-right")]
     PoV2Right,
+    #[serde(rename = "pov2-up")]
     PoV2Up,
     T1,
     T2,
@@ -208,10 +392,7 @@ pub enum BooleanLightMode {
 impl BooleanLightMode {
     /// Returns true if the mode requires animation, i.e. changes over time.
     fn is_animated(&self) -> bool {
-        match self {
-            Self::Flash => true,
-            _ => false,
-        }
+        matches!(self, Self::Flash)
     }
 }
 
@@ -240,13 +421,13 @@ pub enum RedAmberGreenLightMode {
 impl RedAmberGreenLightMode {
     /// Returns true if the mode requires animation, i.e. changes over time.
     fn is_animated(&self) -> bool {
-        match self {
+        !matches!(
+            self,
             RedAmberGreenLightMode::Off
-            | RedAmberGreenLightMode::Red
-            | RedAmberGreenLightMode::Amber
-            | RedAmberGreenLightMode::Green => false,
-            _ => true,
-        }
+                | RedAmberGreenLightMode::Red
+                | RedAmberGreenLightMode::Amber
+                | RedAmberGreenLightMode::Green
+        )
     }
 }
 
@@ -266,20 +447,16 @@ impl LightMode {
 }
 
 /// Common methods for interacting with light mapped to one or more device LEDs.
-trait LightMapping {
+trait LightMapping<T: LedBackend> {
     /// Returns true if the light's currently set mode is animated.
     fn is_animated(&self) -> bool;
 
     /// Updates the light's mode.
     fn set_mode(&mut self, light_mode: LightMode);
 
-    /// Updates the mapped LEDs using the given `DirectOutput` object and based
+    /// Updates the mapped LEDs using the given `LedBackend` object and based
     /// on the current mode and the given `LightModeToStateMapper`.
-    fn update_state(
-        &self,
-        direct_output: &DirectOutput,
-        light_mode_to_state_mapper: &LightModeToStateMapper,
-    );
+    fn update_state(&self, led_backend: &T, light_mode_to_state_mapper: &LightModeToStateMapper);
 }
 
 /// The mapping of a light to a single device LED.
@@ -297,7 +474,7 @@ impl BinaryLightMapping {
     }
 }
 
-impl LightMapping for BinaryLightMapping {
+impl<T: LedBackend> LightMapping<T> for BinaryLightMapping {
     fn is_animated(&self) -> bool {
         self.light_mode.is_animated()
     }
@@ -306,16 +483,8 @@ impl LightMapping for BinaryLightMapping {
         self.light_mode = light_mode.boolean;
     }
 
-    fn update_state(
-        &self,
-        direct_output: &DirectOutput,
-        light_mode_to_state_mapper: &LightModeToStateMapper,
-    ) {
-        light_mode_to_state_mapper.update_binary_light(
-            direct_output,
-            &self.light_mode,
-            self.led_id,
-        );
+    fn update_state(&self, led_backend: &T, light_mode_to_state_mapper: &LightModeToStateMapper) {
+        light_mode_to_state_mapper.update_binary_light(led_backend, &self.light_mode, self.led_id);
     }
 }
 
@@ -336,7 +505,7 @@ impl RedGreenLightMapping {
     }
 }
 
-impl LightMapping for RedGreenLightMapping {
+impl<T: LedBackend> LightMapping<T> for RedGreenLightMapping {
     fn is_animated(&self) -> bool {
         self.light_mode.is_animated()
     }
@@ -345,13 +514,9 @@ impl LightMapping for RedGreenLightMapping {
         self.light_mode = light_mode.red_amber_green;
     }
 
-    fn update_state(
-        &self,
-        direct_output: &DirectOutput,
-        light_mode_to_state_mapper: &LightModeToStateMapper,
-    ) {
+    fn update_state(&self, led_backend: &T, light_mode_to_state_mapper: &LightModeToStateMapper) {
         light_mode_to_state_mapper.update_red_amber_green_light(
-            direct_output,
+            led_backend,
             &self.light_mode,
             self.red_led_id,
             self.green_led_id,
@@ -386,6 +551,7 @@ fn light_for_input(input: Input) -> Light {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::x52pro::RecordingLedBackend;
 
     #[test]
     fn input_to_light_permutations() {
@@ -411,4 +577,103 @@ mod tests {
     fn assert_light_for_input(input: Input, light: Light) {
         assert_eq!(light_for_input(input), light);
     }
+
+    #[test]
+    fn add_mfd_pages_adds_missing_pages_and_scrolls_around_them() {
+        let mut device = Device::with_led_backend(RecordingLedBackend::new());
+
+        device.add_mfd_pages(3);
+        assert_eq!(device.led_backend().pages(), vec![0, 1, 2]);
+
+        device.scroll_mfd_page(false);
+        assert_eq!(device.mfd_page(), 2);
+        device.scroll_mfd_page(true);
+        assert_eq!(device.mfd_page(), 0);
+    }
+
+    #[test]
+    fn scroll_mfd_page_writes_to_page_scrolled_to() {
+        let mut device = Device::with_led_backend(RecordingLedBackend::new());
+        device.add_mfd_pages(2);
+        device.set_mfd_lines(vec![String::from("Sol")]);
+
+        device.scroll_mfd_page(true);
+        device.set_mfd_lines(vec![String::from("Cargo")]);
+        device.activate_page(0);
+
+        // The lines written after scrolling went to the second page.
+        assert_eq!(device.led_backend().mfd_line(0), Some(String::from("Sol")));
+    }
+
+    #[test]
+    fn activate_page_rewrites_lights_and_mfd_lines_to_page() {
+        let mut device = Device::with_led_backend(RecordingLedBackend::new());
+        device.add_mfd_pages(2);
+        device.set_mfd_lines(vec![String::from("Sol")]);
+        let writes = device.led_backend().timeline().len();
+
+        device.activate_page(1);
+        device.set_mfd_lines(vec![String::from("Sol")]);
+
+        let backend = device.led_backend();
+        assert_eq!(device.mfd_page(), 1);
+        assert_eq!(backend.timeline().len(), writes + 20);
+        assert_eq!(backend.mfd_line(0), Some(String::from("Sol")));
+    }
+
+    #[test]
+    fn set_mfd_lines_truncates_and_blanks_missing_lines() {
+        let mut device = Device::with_led_backend(RecordingLedBackend::new());
+
+        device.set_mfd_lines(vec![
+            String::from("Sol"),
+            String::from("A very long system name"),
+        ]);
+
+        let backend = device.led_backend();
+        assert_eq!(backend.mfd_line(0), Some(String::from("Sol")));
+        assert_eq!(backend.mfd_line(1), Some(String::from("A very long syst")));
+        assert_eq!(backend.mfd_line(2), None);
+    }
+
+    #[test]
+    fn set_clock_sets_backend_clock() {
+        let mut device = Device::with_led_backend(RecordingLedBackend::new());
+
+        device.set_clock(13, 5);
+
+        assert_eq!(device.led_backend().clock(), Some((13, 5)));
+    }
+
+    #[test]
+    fn disconnected_device_keeps_lights_until_reconnected() {
+        let mut device = Device::with_led_backend(RecordingLedBackend::new());
+        device.add_mfd_pages(2);
+        let writes = device.led_backend().timeline().len();
+
+        let off = LightMode::new(BooleanLightMode::Off, RedAmberGreenLightMode::Off);
+        let on = LightMode::new(BooleanLightMode::On, RedAmberGreenLightMode::Off);
+
+        device.led_backend().set_plugged_in(false);
+        device.set_input_status_levels(
+            vec![],
+            vec![(Input::Fire, on)],
+            &StatusLevelToModeMapper::new(off, off, off, off),
+        );
+        device.set_mfd_lines(vec![String::from("Sol")]);
+        assert!(!device.is_connected());
+        assert!(!device.reconnect());
+        assert_eq!(device.led_backend().timeline().len(), writes);
+        assert_eq!(device.led_backend().mfd_line(0), None);
+
+        device.led_backend().set_plugged_in(true);
+        assert!(device.reconnect());
+        device.set_mfd_lines(vec![String::from("Sol")]);
+
+        let backend = device.led_backend();
+        assert_eq!(backend.timeline().len(), writes + 20);
+        assert_eq!(backend.led_state(Led::Fire), Some(true));
+        assert_eq!(backend.pages(), vec![0, 1, 0, 1]);
+        assert_eq!(backend.mfd_line(0), Some(String::from("Sol")));
+    }
 }
@@ -0,0 +1,134 @@
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The serial numbers of the joysticks claimed by the selectors sharing it, so
+/// that no two instances of a backend drive the same joystick.
+pub type ClaimedSerials = Rc<RefCell<Vec<String>>>;
+
+/// Chooses which of the connected X52 Pro joysticks a backend drives. The
+/// joystick is identified by its serial number, either configured or taken
+/// from the joystick first chosen by its number, so the same joystick is
+/// chosen again when it's plugged back in and no other is chosen meanwhile.
+/// The joystick is released for other selectors when this is dropped.
+pub struct DeviceSelector {
+    // The number of the joystick to choose in the order they're found.
+    index: usize,
+    serial: Option<String>,
+    claimed: ClaimedSerials,
+}
+
+impl DeviceSelector {
+    /// Returns a new selector for the joystick with the given number, or the
+    /// given serial number if any, which is claimed straight away so that no
+    /// other selector sharing the given claims chooses it.
+    pub fn new(index: usize, serial: Option<String>, claimed: ClaimedSerials) -> Self {
+        if let Some(serial) = &serial {
+            claim(&mut claimed.borrow_mut(), serial);
+        }
+
+        Self {
+            index,
+            serial,
+            claimed,
+        }
+    }
+
+    /// Given the serial numbers of the connected joysticks in the order
+    /// they're found, returns the position of the one to drive, if it's
+    /// connected.
+    pub fn select(&mut self, serials: &[String]) -> Option<usize> {
+        if let Some(serial) = &self.serial {
+            return serials.iter().position(|other| other == serial);
+        }
+
+        // Until a joystick is claimed the one with the selector's number is
+        // chosen, or the first unclaimed one if another selector has it.
+        let mut claimed = self.claimed.borrow_mut();
+        let unclaimed = |serial: &String| !claimed.contains(serial);
+        let position = if serials.get(self.index).is_some_and(unclaimed) {
+            self.index
+        } else {
+            serials.iter().position(unclaimed)?
+        };
+
+        info!("Lighting X52 Pro with serial number {}", serials[position]);
+        claim(&mut claimed, &serials[position]);
+        self.serial = Some(serials[position].clone());
+        Some(position)
+    }
+}
+
+impl Drop for DeviceSelector {
+    fn drop(&mut self) {
+        if let Some(serial) = &self.serial {
+            self.claimed.borrow_mut().retain(|other| other != serial);
+        }
+    }
+}
+
+fn claim(claimed: &mut Vec<String>, serial: &str) {
+    if !claimed.iter().any(|other| other == serial) {
+        claimed.push(serial.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serials(serials: &[&str]) -> Vec<String> {
+        serials.iter().map(|serial| serial.to_string()).collect()
+    }
+
+    fn claims(serials: &[&str]) -> ClaimedSerials {
+        Rc::new(RefCell::new(self::serials(serials)))
+    }
+
+    #[test]
+    fn select_chooses_joystick_by_number_and_keeps_it() {
+        let claimed = claims(&[]);
+        let mut selector = DeviceSelector::new(0, None, Rc::clone(&claimed));
+
+        assert_eq!(selector.select(&serials(&["A", "B"])), Some(0));
+        assert_eq!(*claimed.borrow(), serials(&["A"]));
+        // The joystick isn't replaced by another when it's unplugged.
+        assert_eq!(selector.select(&serials(&["B"])), None);
+        assert_eq!(selector.select(&serials(&["B", "A"])), Some(1));
+    }
+
+    #[test]
+    fn select_skips_joysticks_claimed_by_other_selectors() {
+        let claimed = claims(&["A"]);
+        let mut selector = DeviceSelector::new(0, None, Rc::clone(&claimed));
+
+        assert_eq!(selector.select(&serials(&["A"])), None);
+        assert_eq!(selector.select(&serials(&["A", "B"])), Some(1));
+        assert_eq!(*claimed.borrow(), serials(&["A", "B"]));
+    }
+
+    #[test]
+    fn select_chooses_joystick_by_configured_serial() {
+        let claimed = claims(&[]);
+        let mut selector = DeviceSelector::new(0, Some(String::from("B")), Rc::clone(&claimed));
+        let mut other = DeviceSelector::new(0, None, Rc::clone(&claimed));
+
+        assert_eq!(selector.select(&serials(&["A"])), None);
+        assert_eq!(selector.select(&serials(&["A", "B"])), Some(1));
+        // The configured joystick is never chosen by another selector.
+        assert_eq!(other.select(&serials(&["B"])), None);
+    }
+
+    #[test]
+    fn dropping_selector_releases_its_joystick() {
+        let claimed = claims(&[]);
+        let mut selector = DeviceSelector::new(0, None, Rc::clone(&claimed));
+        selector.select(&serials(&["A"]));
+
+        drop(selector);
+
+        assert!(claimed.borrow().is_empty());
+        let mut other = DeviceSelector::new(1, None, Rc::clone(&claimed));
+        assert_eq!(other.select(&serials(&["A"])), Some(0));
+    }
+}
@@ -1,9 +1,16 @@
+use crate::x52pro::{
+    ClaimedSerials, DeviceCallback, DeviceEvent, DeviceSelector, LedBackend, SoftButton,
+};
 use libc::c_void;
 use libloading::{os::windows::Symbol, Library};
-use log::debug;
+use log::{debug, warn};
+use std::cell::Cell;
 use std::ffi::OsStr;
 use std::iter::once;
 use std::os::windows::ffi::OsStrExt;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, Weak};
 use winapi::ctypes::wchar_t;
 use winapi::shared::minwindef::DWORD;
 use winapi::um::winnt::HRESULT;
@@ -14,62 +21,180 @@ type DeviceHandle = *const c_void;
 
 type InitializeFn = unsafe extern "C" fn(wszPluginName: *const wchar_t) -> HRESULT;
 type EnumerateFn =
-    unsafe extern "C" fn(pfnCb: EnumerateCallbackFn, pCtxt: &mut DirectOutput) -> HRESULT;
-type EnumerateCallbackFn = extern "C" fn(hDevice: DeviceHandle, pCtxt: &mut DirectOutput);
+    unsafe extern "C" fn(pfnCb: EnumerateCallbackFn, pCtxt: &mut Vec<DeviceHandle>) -> HRESULT;
+type EnumerateCallbackFn = extern "C" fn(hDevice: DeviceHandle, pCtxt: &mut Vec<DeviceHandle>);
+type GetDeviceTypeFn = unsafe extern "C" fn(hDevice: DeviceHandle, pGdDevice: *mut Guid) -> HRESULT;
+type GetSerialNumberFn = unsafe extern "C" fn(
+    hDevice: DeviceHandle,
+    pszSerialNumber: *mut wchar_t,
+    dwSize: DWORD,
+) -> HRESULT;
 type AddPageFn = unsafe extern "C" fn(
     hDevice: DeviceHandle,
     dwPage: DWORD,
     wszDebugName: *const wchar_t,
     dwFlags: DWORD,
 ) -> HRESULT;
+type RemovePageFn = unsafe extern "C" fn(hDevice: DeviceHandle, dwPage: DWORD) -> HRESULT;
 type SetLedFn = unsafe extern "C" fn(
     hDevice: DeviceHandle,
     dwPage: DWORD,
     dwIndex: DWORD,
     dwValue: DWORD,
 ) -> HRESULT;
+type SetStringFn = unsafe extern "C" fn(
+    hDevice: DeviceHandle,
+    dwPage: DWORD,
+    dwIndex: DWORD,
+    cchValue: DWORD,
+    wszValue: *const wchar_t,
+) -> HRESULT;
+type DeviceChangeCallbackFn =
+    extern "C" fn(hDevice: DeviceHandle, bAdded: bool, pCtxt: *const c_void);
+type RegisterDeviceCallbackFn =
+    unsafe extern "C" fn(pfnCb: DeviceChangeCallbackFn, pCtxt: *const c_void) -> HRESULT;
+type PageCallbackFn =
+    extern "C" fn(hDevice: DeviceHandle, dwPage: DWORD, bSetActive: bool, pCtxt: *const c_void);
+type RegisterPageCallbackFn = unsafe extern "C" fn(
+    hDevice: DeviceHandle,
+    pfnCb: PageCallbackFn,
+    pCtxt: *const c_void,
+) -> HRESULT;
+type SoftButtonCallbackFn =
+    extern "C" fn(hDevice: DeviceHandle, dwButtons: DWORD, pCtxt: *const c_void);
+type RegisterSoftButtonCallbackFn = unsafe extern "C" fn(
+    hDevice: DeviceHandle,
+    pfnCb: SoftButtonCallbackFn,
+    pCtxt: *const c_void,
+) -> HRESULT;
 
 const FLAG_SET_AS_ACTIVE: DWORD = 1;
 
+/// Mirrors the Windows `GUID` structure, which identifies device types.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Guid {
+    data1: u32,
+    data2: u16,
+    data3: u16,
+    data4: [u8; 8],
+}
+
+// The device type of the X52 Pro, as defined in the SDK header. Other Saitek
+// devices such as the Flight Instrument Panel have their own types.
+const X52PRO_DEVICE_TYPE: Guid = Guid {
+    data1: 0x29dad506,
+    data2: 0xf93b,
+    data3: 0x4f20,
+    data4: [0x85, 0xfa, 0x1e, 0x02, 0xc0, 0x4f, 0xac, 0x17],
+};
+
+// The longest serial number read from a device, in characters.
+const SERIAL_NUMBER_LENGTH: usize = 64;
+
+// The result of a call given a device handle that's no longer valid, e.g.
+// because the device has been unplugged.
+const E_HANDLE: HRESULT = 0x80070006_u32 as HRESULT;
+
 const PLUGIN_NAME: &str = "EDXLC";
-const PAGE_ID: DWORD = 1;
+// The library's page ids are our page indexes offset by this.
+const FIRST_PAGE_ID: DWORD = 1;
+
+const SOFT_BUTTON_SELECT: DWORD = 0x00000001;
+const SOFT_BUTTON_UP: DWORD = 0x00000002;
+const SOFT_BUTTON_DOWN: DWORD = 0x00000004;
 
 const REGISTRY_KEY_NAME: &str = r"DirectOutput";
 const REGISTRY_KEY_PATH: &str = r"SOFTWARE\Logitech\DirectOutput";
 
-/// An instance of a safe wrapper around the Saitek DirectOutput library.
-pub struct DirectOutput {
+/// What the library is given to pass back to the callbacks, which it calls
+/// from its own thread.
+struct CallbackContext {
+    callback: DeviceCallback,
+    // The number of pages added, so that the ids of pages added by other
+    // plugins aren't taken for ours.
+    page_count: Arc<AtomicU32>,
+}
+
+// The callbacks of every instance driving a device, all of which are called
+// when a device is plugged in.
+type CallbackContexts = Mutex<Vec<Weak<CallbackContext>>>;
+
+/// A safe wrapper around the Saitek DirectOutput library, which is loaded and
+/// initialized once and shared by the `DirectOutput` instances driving each
+/// device. The library only calls the device callback last registered so it's
+/// registered here, once, for all of them.
+pub struct DirectOutputLibrary {
     // We have to continue to own the Library instance even though we never use
     // it again so that it is not dropped and hence closed, which would
     // invalidate the symbols loaded from it we want to use to call functions.
     #[allow(dead_code)]
     library: Library,
-    initialize_fn: Symbol<InitializeFn>,
     enumerate_fn: Symbol<EnumerateFn>,
+    get_device_type_fn: Symbol<GetDeviceTypeFn>,
+    get_serial_number_fn: Symbol<GetSerialNumberFn>,
     add_page_fn: Symbol<AddPageFn>,
+    remove_page_fn: Symbol<RemovePageFn>,
     set_led_fn: Symbol<SetLedFn>,
-    device: DeviceHandle,
+    set_string_fn: Symbol<SetStringFn>,
+    register_page_callback_fn: Symbol<RegisterPageCallbackFn>,
+    register_soft_button_callback_fn: Symbol<RegisterSoftButtonCallbackFn>,
+    claimed: ClaimedSerials,
+    // The library is given a pointer to these as the device callback context,
+    // which the `Rc` keeps in place for as long as the library is loaded.
+    contexts: CallbackContexts,
 }
 
-impl DirectOutput {
-    /// Returns a new instance of the library loaded from its default
-    /// installation location. Panics is the library cannot be loaded, e.g. not
-    /// installed at the given location.
-    pub fn load() -> Self {
+impl DirectOutputLibrary {
+    /// Returns the library loaded from its default installation location and
+    /// initialized, for the instances driving devices to share. Panics if the
+    /// library cannot be loaded, e.g. not installed at the given location, or
+    /// initialized.
+    pub fn load() -> Rc<Self> {
         let library = Self::load_library();
-        let initialize_fn = Self::get_library_symbol(&library, b"DirectOutput_Initialize");
-        let enumerate_fn = Self::get_library_symbol(&library, b"DirectOutput_Enumerate");
-        let add_page_fn = Self::get_library_symbol(&library, b"DirectOutput_AddPage");
-        let set_led_fn = Self::get_library_symbol(&library, b"DirectOutput_SetLed");
+        // These are only called here so needn't be kept.
+        let initialize_fn =
+            *Self::get_library_symbol::<InitializeFn>(&library, b"DirectOutput_Initialize");
+        let register_device_callback_fn = *Self::get_library_symbol::<RegisterDeviceCallbackFn>(
+            &library,
+            b"DirectOutput_RegisterDeviceCallback",
+        );
 
-        Self {
+        let directoutput = Rc::new(Self {
+            enumerate_fn: Self::get_library_symbol(&library, b"DirectOutput_Enumerate"),
+            get_device_type_fn: Self::get_library_symbol(&library, b"DirectOutput_GetDeviceType"),
+            get_serial_number_fn: Self::get_library_symbol(
+                &library,
+                b"DirectOutput_GetSerialNumber",
+            ),
+            add_page_fn: Self::get_library_symbol(&library, b"DirectOutput_AddPage"),
+            remove_page_fn: Self::get_library_symbol(&library, b"DirectOutput_RemovePage"),
+            set_led_fn: Self::get_library_symbol(&library, b"DirectOutput_SetLed"),
+            set_string_fn: Self::get_library_symbol(&library, b"DirectOutput_SetString"),
+            register_page_callback_fn: Self::get_library_symbol(
+                &library,
+                b"DirectOutput_RegisterPageCallback",
+            ),
+            register_soft_button_callback_fn: Self::get_library_symbol(
+                &library,
+                b"DirectOutput_RegisterSoftButtonCallback",
+            ),
             library,
-            initialize_fn,
-            enumerate_fn,
-            add_page_fn,
-            set_led_fn,
-            device: std::ptr::null(),
+            claimed: ClaimedSerials::default(),
+            contexts: Mutex::new(vec![]),
+        });
+
+        unsafe {
+            let result = initialize_fn(win32_string(PLUGIN_NAME).as_ptr());
+            debug!("DirectOutput_Initialize result = {:?}", result);
+
+            if result != 0 {
+                panic!("Could not initialize the DirectOutput library");
+            }
         }
+
+        directoutput.register_device_callback(register_device_callback_fn);
+        directoutput
     }
 
     fn load_library() -> Library {
@@ -94,78 +219,378 @@ impl DirectOutput {
         unsafe { library.get::<T>(symbol).unwrap().into_raw() }
     }
 
-    /// Initializes the underlying library. This must be called before any
-    /// other methods can be called. Panics if the initialization fails.
-    pub fn initialize(&self) {
+    /// Registers the callback for devices being plugged in, which calls the
+    /// callbacks of every instance. Panics if the registration fails.
+    fn register_device_callback(&self, register_device_callback_fn: RegisterDeviceCallbackFn) {
+        extern "C" fn device_change_callback(
+            device: DeviceHandle,
+            added: bool,
+            context: *const c_void,
+        ) {
+            debug!("DirectOutput device {:?} added = {}", device, added);
+            let contexts = unsafe { &*(context as *const CallbackContexts) };
+
+            // Removal is noticed when the device is next checked or written to.
+            if added {
+                for context in contexts.lock().unwrap().iter().filter_map(Weak::upgrade) {
+                    (context.callback)(DeviceEvent::Connected);
+                }
+            }
+        }
+
+        let context = &self.contexts as *const CallbackContexts as *const c_void;
+
         unsafe {
-            let result = (self.initialize_fn)(Self::win32_string(PLUGIN_NAME).as_ptr());
-            debug!("DirectOutput_Initialize result = {:?}", result);
+            let result = register_device_callback_fn(device_change_callback, context);
+            debug!("DirectOutput_RegisterDeviceCallback result = {:?}", result);
 
             if result != 0 {
-                panic!("Could not initialize the DirectOutput library");
+                panic!("Could not register device callback with DirectOutput");
             }
         }
     }
 
-    /// Enumerates the connected Saitek devices and selects the last given
-    /// device. This wrapper does not give the ability to select a device by
-    /// type or id but could be extended to do so. For the purposes of this
-    /// project it is currently assuming that only X52Pro devices are attached,
-    /// which may not be true in general. Panics if the enumeration fails.
-    pub fn enumerate(&mut self) {
-        extern "C" fn callback(device: DeviceHandle, target: &mut DirectOutput) {
+    /// Enumerates the connected Saitek devices and returns the X52 Pro devices
+    /// among them with their serial numbers, in the order they're found.
+    /// Other devices, e.g. a Flight Instrument Panel, are ignored. Panics if
+    /// the enumeration fails.
+    fn x52pros(&self) -> Vec<(DeviceHandle, String)> {
+        extern "C" fn callback(device: DeviceHandle, devices: &mut Vec<DeviceHandle>) {
             debug!("DirectOutput_Enumerate device = {:?}", device);
-            target.device = device;
+            devices.push(device);
         }
 
+        let mut devices = Vec::new();
+
         unsafe {
-            let result = (self.enumerate_fn)(callback, self);
+            let result = (self.enumerate_fn)(callback, &mut devices);
             debug!("DirectOutput_Enumerate result = {:?}", result);
 
             if result != 0 {
                 panic!("Could not enumerate dervices with DirectOutput");
             }
         }
+
+        let devices: Vec<(DeviceHandle, Guid)> = devices
+            .into_iter()
+            .filter_map(|device| Some((device, self.device_type(device)?)))
+            .collect();
+        debug!("DirectOutput devices = {:?}", devices);
+
+        let x52pros: Vec<(DeviceHandle, String)> = devices
+            .into_iter()
+            .filter(|(_, device_type)| *device_type == X52PRO_DEVICE_TYPE)
+            .map(|(device, _)| (device, self.serial_number(device)))
+            .collect();
+        debug!("DirectOutput X52 Pro devices = {:?}", x52pros);
+
+        x52pros
     }
 
-    /// Adds a display page to the device. This method must be called after
-    /// `initialize` and before `set_led`. The underlying library supports
-    /// multiple display pages that can be switched between but this wrapper
-    /// creates a single page only. Panics if the addition fails.
-    pub fn add_page(&self) {
+    /// Returns the type of the given device, or `None` if the handle isn't
+    /// valid, e.g. because the device has been unplugged.
+    fn device_type(&self, device: DeviceHandle) -> Option<Guid> {
+        let mut device_type = Guid::default();
+        let result = unsafe { (self.get_device_type_fn)(device, &mut device_type) };
+
+        if result == 0 {
+            Some(device_type)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the serial number of the given device, or its handle if the
+    /// library can't read one, in which case the device isn't recognised when
+    /// it's plugged back in with a new handle.
+    fn serial_number(&self, device: DeviceHandle) -> String {
+        let mut serial_number = [0 as wchar_t; SERIAL_NUMBER_LENGTH];
+        let result = unsafe {
+            (self.get_serial_number_fn)(
+                device,
+                serial_number.as_mut_ptr(),
+                SERIAL_NUMBER_LENGTH as DWORD,
+            )
+        };
+        debug!("DirectOutput_GetSerialNumber result = {:?}", result);
+
+        if result == 0 {
+            let length = serial_number
+                .iter()
+                .position(|character| *character == 0)
+                .unwrap_or(SERIAL_NUMBER_LENGTH);
+            String::from_utf16_lossy(&serial_number[..length])
+        } else {
+            format!("{:?}", device)
+        }
+    }
+}
+
+/// An instance driving one X52 Pro through the shared DirectOutput library.
+pub struct DirectOutput {
+    library: Rc<DirectOutputLibrary>,
+    selector: DeviceSelector,
+    device: DeviceHandle,
+    // Cleared when a call finds the device has gone, which the trait only
+    // lends us a shared reference for.
+    connected: Cell<bool>,
+    page_id: Cell<DWORD>,
+    page_count: Arc<AtomicU32>,
+    // Set once the user has been told the clock can't be set.
+    clock_warned: Cell<bool>,
+    // The library is given a pointer to this as the page and soft button
+    // callback context, and the shared library a weak reference to call it
+    // when a device is plugged in.
+    context: Option<Arc<CallbackContext>>,
+}
+
+impl DirectOutput {
+    /// Returns a new instance that drives the X52 Pro with the given serial
+    /// number, if any, or else the given number in the order they're found,
+    /// through the given shared library. The device is claimed from the
+    /// library until this is dropped.
+    pub fn load(library: &Rc<DirectOutputLibrary>, index: usize, serial: Option<String>) -> Self {
+        Self {
+            library: Rc::clone(library),
+            selector: DeviceSelector::new(index, serial, Rc::clone(&library.claimed)),
+            device: std::ptr::null(),
+            connected: Cell::new(false),
+            page_id: Cell::new(FIRST_PAGE_ID),
+            page_count: Arc::new(AtomicU32::new(0)),
+            clock_warned: Cell::new(false),
+            context: None,
+        }
+    }
+
+    /// Logs the failure of the given action, noting when it failed because
+    /// the device has been unplugged so that it's no longer written to.
+    fn log_failure(&self, result: HRESULT, action: &str) {
+        if result == E_HANDLE {
+            if self.connected.replace(false) {
+                warn!("X52 Pro disconnected");
+            }
+        } else if result != 0 {
+            warn!("Can't {}, return value {:#x}", action, result);
+        }
+    }
+
+    /// Registers the callback for page changes and soft buttons on the
+    /// selected device. Panics if the registration fails.
+    fn register_page_callbacks(&self) {
+        extern "C" fn page_callback(
+            _device: DeviceHandle,
+            page_id: DWORD,
+            active: bool,
+            context: *const c_void,
+        ) {
+            debug!("DirectOutput page {} active = {}", page_id, active);
+            let context = unsafe { &*(context as *const CallbackContext) };
+
+            let page = match page_id.checked_sub(FIRST_PAGE_ID) {
+                Some(page) if page < context.page_count.load(Ordering::Relaxed) => page,
+                _ => return,
+            };
+
+            if active {
+                (context.callback)(DeviceEvent::PageActivated(page));
+            }
+        }
+
+        extern "C" fn soft_button_callback(
+            _device: DeviceHandle,
+            buttons: DWORD,
+            context: *const c_void,
+        ) {
+            debug!("DirectOutput soft buttons = {:#x}", buttons);
+            let context = unsafe { &*(context as *const CallbackContext) };
+
+            // Releasing the buttons is reported with no buttons set.
+            for (button, soft_button) in [
+                (SOFT_BUTTON_SELECT, SoftButton::Select),
+                (SOFT_BUTTON_UP, SoftButton::Up),
+                (SOFT_BUTTON_DOWN, SoftButton::Down),
+            ] {
+                if buttons & button != 0 {
+                    (context.callback)(DeviceEvent::SoftButton(soft_button));
+                }
+            }
+        }
+
+        let context = match &self.context {
+            Some(context) => Arc::as_ptr(context) as *const c_void,
+            None => return,
+        };
+
+        unsafe {
+            let result =
+                (self.library.register_page_callback_fn)(self.device, page_callback, context);
+            debug!("DirectOutput_RegisterPageCallback result = {:?}", result);
+
+            if result != 0 {
+                panic!("Could not register page callback with DirectOutput");
+            }
+
+            let result = (self.library.register_soft_button_callback_fn)(
+                self.device,
+                soft_button_callback,
+                context,
+            );
+            debug!(
+                "DirectOutput_RegisterSoftButtonCallback result = {:?}",
+                result
+            );
+
+            if result != 0 {
+                panic!("Could not register soft button callback with DirectOutput");
+            }
+        }
+    }
+}
+
+impl LedBackend for DirectOutput {
+    /// Does nothing as the shared library is initialized when it's loaded.
+    fn initialize(&self) {}
+
+    /// Enumerates the connected X52 Pro devices and selects the one this
+    /// instance drives, returning false if it isn't found. Panics if the
+    /// enumeration fails.
+    fn enumerate(&mut self) -> bool {
+        let (x52pros, serial_numbers): (Vec<DeviceHandle>, Vec<String>) =
+            self.library.x52pros().into_iter().unzip();
+
+        // Only ever selecting this instance's own device means the page
+        // callbacks never replace those registered by another instance.
+        self.device = match self.selector.select(&serial_numbers) {
+            Some(position) => x52pros[position],
+            None => std::ptr::null(),
+        };
+
+        self.connected.set(!self.device.is_null());
+        // A reconnected device has a new handle so needs the callbacks again.
+        if self.connected.get() {
+            self.register_page_callbacks();
+        }
+
+        self.connected.get()
+    }
+
+    /// Returns false once the device has been unplugged. Writes aren't made
+    /// often enough to notice straight away so the library is asked for the
+    /// type of the device, which fails once its handle is no longer valid.
+    fn is_connected(&self) -> bool {
+        if self.connected.get() && self.library.device_type(self.device).is_none() {
+            self.connected.set(false);
+            warn!("X52 Pro disconnected");
+        }
+
+        self.connected.get()
+    }
+
+    /// Adds a display page to the device, which the user can switch to with
+    /// the page buttons. The first page must be added after `initialize` and
+    /// before `set_led`.
+    fn add_page(&self, page: u32, active: bool) {
         // Despite what the SDK documentation says, we have to pass in a non-null debug
         // name or later calls fail with an error indicating the page is not active.
-        let debug_name = Self::win32_string(PLUGIN_NAME).as_ptr();
+        let debug_name = win32_string(PLUGIN_NAME);
 
         unsafe {
-            let result = (self.add_page_fn)(self.device, PAGE_ID, debug_name, FLAG_SET_AS_ACTIVE);
+            let result = (self.library.add_page_fn)(
+                self.device,
+                FIRST_PAGE_ID + page,
+                debug_name.as_ptr(),
+                if active { FLAG_SET_AS_ACTIVE } else { 0 },
+            );
             debug!("DirectOutput_AddPage result = {:?}", result);
+            self.log_failure(result, "add page");
+        }
 
-            if result != 0 {
-                panic!("Could not add page with DirectOutput");
-            }
+        self.page_count.fetch_max(page + 1, Ordering::Relaxed);
+    }
+
+    /// Writes to the page with the given index from now on. The library
+    /// rejects writes to pages that aren't active.
+    fn set_page(&self, page: u32) {
+        self.page_id.set(FIRST_PAGE_ID + page);
+    }
+
+    /// Makes the page with the given index active by removing it and adding
+    /// it again as the active page, as the library has no call to activate a
+    /// page directly. The page buttons then carry on from this page.
+    fn show_page(&self, page: u32) {
+        unsafe {
+            let result = (self.library.remove_page_fn)(self.device, FIRST_PAGE_ID + page);
+            debug!("DirectOutput_RemovePage result = {:?}", result);
+            self.log_failure(result, "remove page");
+        }
+
+        self.add_page(page, true);
+    }
+
+    /// Registers the given function to be called when the user switches pages,
+    /// presses a soft button or a device is plugged in. The library calls it
+    /// from its own thread.
+    fn register_callback(&mut self, callback: DeviceCallback) {
+        let context = Arc::new(CallbackContext {
+            callback,
+            page_count: Arc::clone(&self.page_count),
+        });
+        self.library
+            .contexts
+            .lock()
+            .unwrap()
+            .push(Arc::downgrade(&context));
+        self.context = Some(context);
+
+        if self.connected.get() {
+            self.register_page_callbacks();
         }
     }
 
     /// Activates or deactives the LED with the given `id` on the joystick. The
-    /// `id` must be between 0 and 19 inclusive for the X52Pro. Panics if
-    /// setting the LED state fails, e.g. if given an invalid `id`.
-    pub fn set_led(&self, id: u32, active: bool) {
+    /// `id` must be between 0 and 19 inclusive for the X52Pro.
+    fn set_led(&self, id: u32, active: bool) {
         let value = if active { 1 } else { 0 };
         debug!("Setting LED {} to {}", id, value);
 
         unsafe {
-            let result = (self.set_led_fn)(self.device, PAGE_ID, id, value);
+            let result = (self.library.set_led_fn)(self.device, self.page_id.get(), id, value);
+            self.log_failure(result, "set LED");
+        }
+    }
 
-            if result != 0 {
-                panic!("Can't set LED, return value {}", result);
-            }
+    /// Sets the text of the given `line` of the MFD on the throttle. The
+    /// `line` must be between 0 and 2 inclusive for the X52Pro.
+    fn set_string(&self, line: u32, text: &str) {
+        debug!("Setting MFD line {} to {:?}", line, text);
+        let value = win32_string(text);
+
+        unsafe {
+            // The length excludes the null terminator.
+            let result = (self.library.set_string_fn)(
+                self.device,
+                self.page_id.get(),
+                line,
+                (value.len() - 1) as DWORD,
+                value.as_ptr(),
+            );
+            self.log_failure(result, "set MFD string");
         }
     }
 
-    /// Given a native string `value` returns a Windows native "wide" string
-    /// suitable for passing to Windows-native code.
-    fn win32_string(value: &str) -> Vec<u16> {
-        OsStr::new(value).encode_wide().chain(once(0)).collect()
+    /// Leaves the clock alone, warning the first time, as the library has no
+    /// call to set it outside of the Saitek software's profiles.
+    fn set_clock(&self, hour: u32, minute: u32) {
+        debug!("Not setting clock to {:02}:{:02}", hour, minute);
+
+        if !self.clock_warned.replace(true) {
+            warn!("Can't set the X52 Pro clock on Windows; the time is on the MFD timers page");
+        }
     }
 }
+
+/// Given a native string `value` returns a Windows native "wide" string
+/// suitable for passing to Windows-native code.
+fn win32_string(value: &str) -> Vec<u16> {
+    OsStr::new(value).encode_wide().chain(once(0)).collect()
+}
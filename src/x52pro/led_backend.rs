@@ -0,0 +1,79 @@
+/// Events raised by the device, e.g. when the user switches MFD pages. Only
+/// the DirectOutput backend raises events.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum DeviceEvent {
+    /// A device has been connected, e.g. the joystick was plugged back in.
+    Connected,
+    /// The page with the given index (from 0) has become the active page.
+    PageActivated(u32),
+    /// A soft button, e.g. the scroll wheel on the throttle, was pressed.
+    SoftButton(SoftButton),
+}
+
+/// The soft buttons on the device used to interact with the MFD.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum SoftButton {
+    Select,
+    Up,
+    Down,
+}
+
+/// A function called with each event raised by the device. It may be called
+/// from a thread owned by the backend.
+pub type DeviceCallback = Box<dyn Fn(DeviceEvent) + Send + Sync>;
+
+/// The operations a device needs from the layer that actually drives the LEDs
+/// and MFD, e.g. the Saitek DirectOutput library.
+pub trait LedBackend {
+    /// Initializes the backend. This must be called before any other methods
+    /// are called.
+    fn initialize(&self);
+
+    /// Enumerates the connected devices and selects one to drive, returning
+    /// false if none are connected. Called again to select a device that has
+    /// been reconnected.
+    fn enumerate(&mut self) -> bool;
+
+    /// Returns false once the selected device has been disconnected, e.g. a
+    /// write to it has failed, until a device is selected again. Backends
+    /// that can't be disconnected are always connected by default.
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    /// Adds the display page with the given index (from 0) to the selected
+    /// device, making it the active page if `active`. The first page must be
+    /// added after `enumerate` and before `set_led`.
+    fn add_page(&self, page: u32, active: bool);
+
+    /// Writes subsequent LED and MFD updates to the page with the given index,
+    /// which the device has made active. Does nothing by default.
+    fn set_page(&self, _page: u32) {}
+
+    /// Makes the page with the given index the active page on the device,
+    /// e.g. when scrolling through the pages. Does nothing by default.
+    fn show_page(&self, _page: u32) {}
+
+    /// Registers the given function to be called with events raised by the
+    /// device. Does nothing by default, i.e. the device raises no events.
+    fn register_callback(&mut self, _callback: DeviceCallback) {}
+
+    /// Activates or deactivates the LED with the given `id` on the device.
+    /// Failures are logged rather than returned so a disconnected device
+    /// doesn't stop the app.
+    fn set_led(&self, id: u32, active: bool);
+
+    /// Sets the text of the given `line` (0 to 2) of the MFD on the device.
+    fn set_string(&self, line: u32, text: &str);
+
+    /// Sets the clock on the device to the given hour (0 to 23) and minute
+    /// in 24-hour format. Does nothing by default, e.g. DirectOutput has no
+    /// function to set the clock outside of a Saitek profile.
+    fn set_clock(&self, _hour: u32, _minute: u32) {}
+
+    /// Presents the LED states set since the last call. Called after each
+    /// batch of updates to the lights. Does nothing by default.
+    fn flush(&self) {}
+}
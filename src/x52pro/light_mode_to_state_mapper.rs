@@ -1,5 +1,5 @@
 use crate::x52pro::device::{BooleanLightMode, Led, RedAmberGreenLightMode};
-use crate::x52pro::direct_output::DirectOutput;
+use crate::x52pro::LedBackend;
 use std::time::SystemTime;
 
 pub const ALERT_FLASH_MILLISECONDS: u128 = 500;
@@ -35,9 +35,9 @@ impl LightModeToStateMapper {
     }
 
     /// Sets the given device LED to the correct state based on the given mode.
-    pub fn update_binary_light(
+    pub fn update_binary_light<T: LedBackend>(
         &self,
-        direct_output: &DirectOutput,
+        led_backend: &T,
         light_mode: &BooleanLightMode,
         led_id: Led,
     ) {
@@ -49,13 +49,13 @@ impl LightModeToStateMapper {
             BooleanLightState::On => true,
         };
 
-        direct_output.set_led(led_id as u32, led_active);
+        led_backend.set_led(led_id as u32, led_active);
     }
 
     /// Sets the given device LEDs to the correct state based on the given mode.
-    pub fn update_red_amber_green_light(
+    pub fn update_red_amber_green_light<T: LedBackend>(
         &self,
-        direct_output: &DirectOutput,
+        led_backend: &T,
         light_mode: &RedAmberGreenLightMode,
         red_led_id: Led,
         green_led_id: Led,
@@ -70,8 +70,8 @@ impl LightModeToStateMapper {
             RedAmberGreenLightState::Green => (false, true),
         };
 
-        direct_output.set_led(red_led_id as u32, red_led_state);
-        direct_output.set_led(green_led_id as u32, green_led_state);
+        led_backend.set_led(red_led_id as u32, red_led_state);
+        led_backend.set_led(green_led_id as u32, green_led_state);
     }
 
     /// Returns the number of milliseconds elapsed since the reference time.
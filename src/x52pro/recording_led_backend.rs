@@ -0,0 +1,165 @@
+use crate::x52pro::{device::Led, LedBackend};
+use std::cell::{Cell, RefCell};
+use std::time::Instant;
+
+/// A single write of an LED state made through a `RecordingLedBackend`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LedWrite {
+    pub led_id: u32,
+    pub active: bool,
+    pub timestamp: Instant,
+}
+
+/// An in-memory `LedBackend` that records the timeline of LED writes made
+/// through it, so the lights can be asserted against without hardware.
+pub struct RecordingLedBackend {
+    // The trait only lends us a shared reference when setting LEDs.
+    timeline: RefCell<Vec<LedWrite>>,
+    mfd_lines: RefCell<Vec<(u32, u32, String)>>,
+    pages: RefCell<Vec<u32>>,
+    shown_pages: RefCell<Vec<u32>>,
+    page: Cell<u32>,
+    clock: Cell<Option<(u32, u32)>>,
+    plugged_in: Cell<bool>,
+    connected: Cell<bool>,
+}
+
+impl RecordingLedBackend {
+    /// Returns a new instance with an empty timeline.
+    pub fn new() -> Self {
+        Self {
+            timeline: RefCell::new(vec![]),
+            mfd_lines: RefCell::new(vec![]),
+            pages: RefCell::new(vec![]),
+            shown_pages: RefCell::new(vec![]),
+            page: Cell::new(0),
+            clock: Cell::new(None),
+            plugged_in: Cell::new(true),
+            connected: Cell::new(true),
+        }
+    }
+
+    /// Returns all the LED writes recorded so far, oldest first.
+    pub fn timeline(&self) -> Vec<LedWrite> {
+        self.timeline.borrow().clone()
+    }
+
+    /// Returns the most recently written state of the given LED, or `None` if
+    /// it has never been written.
+    pub fn led_state(&self, led: Led) -> Option<bool> {
+        self.timeline
+            .borrow()
+            .iter()
+            .rev()
+            .find(|write| write.led_id == led as u32)
+            .map(|write| write.active)
+    }
+
+    /// Returns the most recently written text of the given MFD line on the
+    /// current page, or `None` if it has never been written.
+    pub fn mfd_line(&self, line: u32) -> Option<String> {
+        self.mfd_lines
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(page, written_line, _)| *page == self.page.get() && *written_line == line)
+            .map(|(_, _, text)| text.clone())
+    }
+
+    /// Returns the most recently set clock time as the hour and minute, or
+    /// `None` if it has never been set.
+    pub fn clock(&self) -> Option<(u32, u32)> {
+        self.clock.get()
+    }
+
+    /// Simulates the device being unplugged or plugged back in. Once plugged
+    /// back in it's only connected again when next enumerated.
+    pub fn set_plugged_in(&self, plugged_in: bool) {
+        self.plugged_in.set(plugged_in);
+        if !plugged_in {
+            self.connected.set(false);
+        }
+    }
+
+    /// Returns the indexes of the pages added so far.
+    pub fn pages(&self) -> Vec<u32> {
+        self.pages.borrow().clone()
+    }
+
+    /// Returns the indexes of the pages shown so far, oldest first.
+    pub fn shown_pages(&self) -> Vec<u32> {
+        self.shown_pages.borrow().clone()
+    }
+}
+
+impl LedBackend for RecordingLedBackend {
+    fn initialize(&self) {}
+
+    fn enumerate(&mut self) -> bool {
+        self.connected.set(self.plugged_in.get());
+        self.connected.get()
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.get()
+    }
+
+    fn add_page(&self, page: u32, _active: bool) {
+        self.pages.borrow_mut().push(page);
+    }
+
+    fn set_page(&self, page: u32) {
+        self.page.set(page);
+    }
+
+    /// Records the page as shown and, like DirectOutput, which adds the page
+    /// again to show it, forgets the text written to it.
+    fn show_page(&self, page: u32) {
+        self.shown_pages.borrow_mut().push(page);
+        self.mfd_lines
+            .borrow_mut()
+            .retain(|(written_page, _, _)| *written_page != page);
+    }
+
+    fn set_led(&self, id: u32, active: bool) {
+        self.timeline.borrow_mut().push(LedWrite {
+            led_id: id,
+            active,
+            timestamp: Instant::now(),
+        });
+    }
+
+    fn set_string(&self, line: u32, text: &str) {
+        self.mfd_lines
+            .borrow_mut()
+            .push((self.page.get(), line, text.to_string()));
+    }
+
+    fn set_clock(&self, hour: u32, minute: u32) {
+        self.clock.set(Some((hour, minute)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_led_backend_records_timeline_of_writes() {
+        let backend = RecordingLedBackend::new();
+        backend.set_led(Led::Fire as u32, true);
+        backend.set_led(Led::Throttle as u32, false);
+        backend.set_led(Led::Fire as u32, false);
+
+        let timeline = backend.timeline();
+        let writes: Vec<(u32, bool)> = timeline
+            .iter()
+            .map(|write| (write.led_id, write.active))
+            .collect();
+
+        assert_eq!(writes, vec![(0, true), (19, false), (0, false)]);
+        assert!(timeline[0].timestamp <= timeline[2].timestamp);
+        assert_eq!(backend.led_state(Led::Fire), Some(false));
+        assert_eq!(backend.led_state(Led::ClutchRed), None);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::x52pro::{device::Led, LedBackend};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const LED_COUNT: usize = 20;
+const MFD_LINE_COUNT: usize = 3;
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_AMBER: &str = "\x1b[33m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_WHITE: &str = "\x1b[97m";
+const ANSI_DARK_GREY: &str = "\x1b[90m";
+const ANSI_CLEAR_TO_END_OF_LINE: &str = "\x1b[K";
+
+// Set while the cursor is on the last line of the lights drawn in the terminal,
+// i.e. nothing else has been written since, so they can be drawn over.
+static LIGHTS_DRAWN: AtomicBool = AtomicBool::new(false);
+
+/// The lights on the device in display order, each with its label and the
+/// LEDs that drive it. Boolean lights have no green LED.
+const LIGHTS: [(&str, Led, Option<Led>); 11] = [
+    ("Clutch", Led::ClutchRed, Some(Led::ClutchGreen)),
+    ("Fire", Led::Fire, None),
+    ("A", Led::FireARed, Some(Led::FireAGreen)),
+    ("B", Led::FireBRed, Some(Led::FireBGreen)),
+    ("D", Led::FireDRed, Some(Led::FireDGreen)),
+    ("E", Led::FireERed, Some(Led::FireEGreen)),
+    ("T1/2", Led::T1T2Red, Some(Led::T1T2Green)),
+    ("T3/4", Led::T3T4Red, Some(Led::T3T4Green)),
+    ("T5/6", Led::T5T6Red, Some(Led::T5T6Green)),
+    ("Throttle", Led::Throttle, None),
+    ("PoV2", Led::PoV2Red, Some(Led::PoV2Green)),
+];
+
+/// An `LedBackend` that renders the lights of the device as coloured glyphs
+/// on its own, continuously rewritten, line of the terminal. This allows
+/// configurations to be previewed without the device attached.
+pub struct TerminalLedBackend {
+    // The trait only lends us a shared reference when setting LEDs.
+    led_states: RefCell<[bool; LED_COUNT]>,
+    mfd_lines: RefCell<[String; MFD_LINE_COUNT]>,
+    // The rendered lines of all the devices previewed together, which are
+    // drawn as one block, and the index of this device's line.
+    terminal_lines: Rc<RefCell<Vec<String>>>,
+    terminal_line: usize,
+}
+
+impl TerminalLedBackend {
+    /// Returns an instance for each of the given number of devices, with all
+    /// LEDs off, that draw their lights on consecutive lines.
+    pub fn for_devices(count: usize) -> Vec<Self> {
+        let terminal_lines = Rc::new(RefCell::new(vec![String::new(); count]));
+
+        (0..count)
+            .map(|terminal_line| Self {
+                led_states: RefCell::new([false; LED_COUNT]),
+                mfd_lines: RefCell::new(Default::default()),
+                terminal_lines: Rc::clone(&terminal_lines),
+                terminal_line,
+            })
+            .collect()
+    }
+
+    /// Returns the current state of the LEDs, followed by the MFD lines,
+    /// rendered as a line of text.
+    fn render(&self) -> String {
+        let led_states = self.led_states.borrow();
+
+        let lights = LIGHTS
+            .iter()
+            .map(|(label, led, green_led)| {
+                let colour = match green_led {
+                    Some(green_led) => red_amber_green_colour(
+                        led_states[*led as usize],
+                        led_states[*green_led as usize],
+                    ),
+                    None => boolean_colour(led_states[*led as usize]),
+                };
+
+                format!("{}[{}]{}", colour, label, ANSI_RESET)
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        format!("{} |{}|", lights, self.mfd_lines.borrow().join("|"))
+    }
+}
+
+impl LedBackend for TerminalLedBackend {
+    fn initialize(&self) {}
+
+    fn enumerate(&mut self) -> bool {
+        true
+    }
+
+    fn add_page(&self, _page: u32, _active: bool) {}
+
+    fn set_led(&self, id: u32, active: bool) {
+        self.led_states.borrow_mut()[id as usize] = active;
+    }
+
+    fn set_string(&self, line: u32, text: &str) {
+        self.mfd_lines.borrow_mut()[line as usize] = text.to_string();
+    }
+
+    /// Rewrites the device's line in the terminal with the current state of
+    /// the LEDs, drawing the lines of all the devices again.
+    fn flush(&self) {
+        self.terminal_lines.borrow_mut()[self.terminal_line] = self.render();
+        let terminal_lines = self.terminal_lines.borrow();
+
+        let mut stdout = io::stdout();
+        // Go back to the first line of the lights if they're still there.
+        if LIGHTS_DRAWN.swap(true, Ordering::SeqCst) && terminal_lines.len() > 1 {
+            write!(stdout, "\x1b[{}A", terminal_lines.len() - 1)
+                .expect("Can't write lights to terminal");
+        }
+        write!(
+            stdout,
+            "\r{}{}",
+            terminal_lines.join(&format!("{}\n", ANSI_CLEAR_TO_END_OF_LINE)),
+            ANSI_CLEAR_TO_END_OF_LINE
+        )
+        .expect("Can't write lights to terminal");
+        stdout.flush().expect("Can't flush lights to terminal");
+    }
+}
+
+/// Moves the cursor past the lights drawn in the terminal, if any, so that
+/// other output, e.g. a log message, starts on a new line rather than in the
+/// middle of them. The lights are drawn again below on the next flush.
+pub fn end_terminal_lights() {
+    if LIGHTS_DRAWN.swap(false, Ordering::SeqCst) {
+        let mut stdout = io::stdout();
+        writeln!(stdout).expect("Can't write to terminal");
+        stdout.flush().expect("Can't flush terminal");
+    }
+}
+
+/// Returns the terminal colour for a light with a single LED.
+fn boolean_colour(active: bool) -> &'static str {
+    if active {
+        ANSI_WHITE
+    } else {
+        ANSI_DARK_GREY
+    }
+}
+
+/// Returns the terminal colour for a light with a red-green pair of LEDs.
+fn red_amber_green_colour(red_active: bool, green_active: bool) -> &'static str {
+    match (red_active, green_active) {
+        (false, false) => ANSI_DARK_GREY,
+        (true, false) => ANSI_RED,
+        (true, true) => ANSI_AMBER,
+        (false, true) => ANSI_GREEN,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_colours_lights_from_led_states() {
+        let backend = TerminalLedBackend::for_devices(1).remove(0);
+        backend.set_led(Led::ClutchRed as u32, true);
+        backend.set_led(Led::ClutchGreen as u32, true);
+        backend.set_led(Led::FireARed as u32, true);
+        backend.set_led(Led::FireBGreen as u32, true);
+        backend.set_led(Led::Throttle as u32, true);
+
+        let rendered = backend.render();
+
+        assert!(rendered.starts_with("\x1b[33m[Clutch]\x1b[0m \x1b[90m[Fire]\x1b[0m"));
+        assert!(rendered.contains("\x1b[31m[A]\x1b[0m \x1b[32m[B]\x1b[0m \x1b[90m[D]\x1b[0m"));
+        assert!(rendered.contains("\x1b[97m[Throttle]\x1b[0m"));
+    }
+
+    #[test]
+    fn for_devices_gives_each_device_its_own_line() {
+        let backends = TerminalLedBackend::for_devices(2);
+
+        assert_eq!(backends[0].terminal_line, 0);
+        assert_eq!(backends[1].terminal_line, 1);
+        assert!(Rc::ptr_eq(
+            &backends[0].terminal_lines,
+            &backends[1].terminal_lines
+        ));
+    }
+
+    #[test]
+    fn render_appends_mfd_lines() {
+        let backend = TerminalLedBackend::for_devices(1).remove(0);
+        backend.set_string(0, "Sol");
+        backend.set_string(2, "Fuel 50%");
+
+        assert!(backend.render().ends_with(" |Sol||Fuel 50%|"));
+    }
+}
@@ -0,0 +1,317 @@
+use crate::x52pro::{ClaimedSerials, DeviceSelector, LedBackend};
+use libc::{c_ulong, c_void};
+use log::{debug, warn};
+use std::cell::Cell;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::mem::size_of;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+// See: https://github.com/nirenjan/x52pro-linux (libx52)
+const SAITEK_VENDOR_ID: &str = "06a3";
+const X52PRO_PRODUCT_ID: &str = "0762";
+
+const USB_DEVICES_PATH: &str = "/sys/bus/usb/devices";
+const USB_DEVICE_FILES_PATH: &str = "/dev/bus/usb";
+
+const REQUEST_TYPE_VENDOR_OUT: u8 = 0x40;
+const X52PRO_VENDOR_REQUEST: u8 = 0x91;
+const X52PRO_LED_COMMAND: u16 = 0x00b8;
+const X52PRO_MFD_LINE_COMMANDS: [u16; 3] = [0x00d1, 0x00d2, 0x00d4];
+const X52PRO_MFD_CLEAR_LINE: u16 = 0x0008;
+const X52PRO_CLOCK_COMMAND: u16 = 0x00c0;
+const X52PRO_CLOCK_24_HOUR: u16 = 0x8000;
+const TIMEOUT_MILLISECONDS: u32 = 1000;
+
+/// Mirrors the kernel's `usbdevfs_ctrltransfer` structure.
+#[repr(C)]
+struct ControlTransfer {
+    request_type: u8,
+    request: u8,
+    value: u16,
+    index: u16,
+    length: u16,
+    timeout: u32,
+    data: *mut c_void,
+}
+
+// Equivalent to the kernel's `_IOWR('U', 0, struct usbdevfs_ctrltransfer)`.
+const USBDEVFS_CONTROL: c_ulong =
+    (3 << 30) | ((size_of::<ControlTransfer>() as c_ulong) << 16) | ((b'U' as c_ulong) << 8);
+
+/// The X52 Pro devices on the USB bus, shared by the instances driving them
+/// so that no two drive the same device.
+#[derive(Default)]
+pub struct UsbDevices {
+    claimed: ClaimedSerials,
+}
+
+impl UsbDevices {
+    /// Returns a new instance for the instances driving devices to share.
+    pub fn load() -> Rc<Self> {
+        Rc::new(Self::default())
+    }
+}
+
+/// An instance of a wrapper around vendor-specific USB control transfers sent
+/// directly to the device through the Linux usbfs interface, as an alternative
+/// to the Windows-only DirectOutput library. The user must have write access
+/// to the device file, e.g. through a udev rule.
+pub struct UsbControl {
+    selector: DeviceSelector,
+    device: Option<File>,
+    // Cleared when a transfer finds the device has gone, which the trait only
+    // lends us a shared reference for.
+    connected: Cell<bool>,
+}
+
+impl UsbControl {
+    /// Returns a new instance that drives the device with the given serial
+    /// number, if any, or else the given number in the order they're found,
+    /// but isn't yet associated with it. The device is claimed from the given
+    /// shared devices until this is dropped.
+    pub fn load(devices: &Rc<UsbDevices>, index: usize, serial: Option<String>) -> Self {
+        Self {
+            selector: DeviceSelector::new(index, serial, Rc::clone(&devices.claimed)),
+            device: None,
+            connected: Cell::new(false),
+        }
+    }
+
+    /// Returns the serial numbers and paths of the device files for all
+    /// connected X52 Pro devices, as found by their vendor and product ids in
+    /// sysfs. They're ordered by the USB port they're plugged into, which
+    /// unlike the device number doesn't change when a device is plugged back
+    /// in. A device without a serial number is identified by its port instead.
+    fn device_file_paths() -> Vec<(String, PathBuf)> {
+        let entries = fs::read_dir(USB_DEVICES_PATH).expect("Can't read USB devices from sysfs");
+
+        let mut device_paths: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                sysfs_attribute(path, "idVendor").as_deref() == Some(SAITEK_VENDOR_ID)
+                    && sysfs_attribute(path, "idProduct").as_deref() == Some(X52PRO_PRODUCT_ID)
+            })
+            .collect();
+        device_paths.sort();
+
+        device_paths
+            .into_iter()
+            .filter_map(|path| {
+                let bus_number = sysfs_attribute(&path, "busnum")?.parse().ok()?;
+                let device_number = sysfs_attribute(&path, "devnum")?.parse().ok()?;
+                let serial = sysfs_attribute(&path, "serial").or_else(|| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                })?;
+                Some((serial, device_file_path(bus_number, device_number)))
+            })
+            .collect()
+    }
+
+    /// Sends a vendor-specific control transfer with the given index and value
+    /// to the open device.
+    fn vendor_command(&self, index: u16, value: u16) -> io::Result<()> {
+        let device = match &self.device {
+            Some(device) => device,
+            None => return Err(io::Error::from_raw_os_error(libc::ENODEV)),
+        };
+        let mut transfer = ControlTransfer {
+            request_type: REQUEST_TYPE_VENDOR_OUT,
+            request: X52PRO_VENDOR_REQUEST,
+            value,
+            index,
+            length: 0,
+            timeout: TIMEOUT_MILLISECONDS,
+            data: std::ptr::null_mut(),
+        };
+
+        let result = unsafe { libc::ioctl(device.as_raw_fd(), USBDEVFS_CONTROL, &mut transfer) };
+
+        if result < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Logs the failure of the given action, noting when it failed because
+    /// the device has been unplugged so that it's no longer written to.
+    fn log_failure(&self, result: io::Result<()>, action: &str) {
+        match result {
+            Ok(()) => (),
+            Err(error) if error.raw_os_error() == Some(libc::ENODEV) => {
+                if self.connected.replace(false) {
+                    warn!("X52 Pro disconnected");
+                }
+            }
+            Err(error) => warn!("Can't {}, error {}", action, error),
+        }
+    }
+}
+
+impl LedBackend for UsbControl {
+    /// Does nothing as there is no library to initialize.
+    fn initialize(&self) {}
+
+    /// Finds the connected X52 Pro devices and opens the one this instance
+    /// drives, returning false if it isn't found. Panics if the
+    /// device file can't be opened for lack of permission.
+    fn enumerate(&mut self) -> bool {
+        self.device = None;
+        self.connected.set(false);
+
+        let device_file_paths = Self::device_file_paths();
+        debug!("X52 Pro USB device files = {:?}", device_file_paths);
+
+        let (serials, paths): (Vec<String>, Vec<PathBuf>) = device_file_paths.into_iter().unzip();
+        let path = match self.selector.select(&serials) {
+            Some(position) => &paths[position],
+            None => return false,
+        };
+        debug!("X52 Pro USB device file = {:?}", path);
+
+        match OpenOptions::new().read(true).write(true).open(path) {
+            Ok(device) => {
+                self.device = Some(device);
+                self.connected.set(true);
+                true
+            }
+            Err(error) if error.kind() == io::ErrorKind::PermissionDenied => {
+                panic!("Could not open X52 Pro USB device file; do you have permission?")
+            }
+            Err(error) => {
+                warn!("Could not open X52 Pro USB device file, error {}", error);
+                false
+            }
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.get()
+    }
+
+    /// Does nothing as the device has no display pages over USB.
+    fn add_page(&self, _page: u32, _active: bool) {}
+
+    /// Activates or deactives the LED with the given `id` on the joystick. The
+    /// `id` must be between 0 and 19 inclusive, i.e. the same as DirectOutput.
+    fn set_led(&self, id: u32, active: bool) {
+        debug!("Setting LED {} to {}", id, active);
+
+        let result = self.vendor_command(X52PRO_LED_COMMAND, led_command_value(id, active));
+        self.log_failure(result, "set LED");
+    }
+
+    /// Sets the text of the given `line` (0 to 2) of the MFD on the throttle by
+    /// clearing the line then writing the characters two at a time.
+    fn set_string(&self, line: u32, text: &str) {
+        debug!("Setting MFD line {} to {:?}", line, text);
+        let command = X52PRO_MFD_LINE_COMMANDS[line as usize];
+
+        let result = self
+            .vendor_command(command | X52PRO_MFD_CLEAR_LINE, 0)
+            .and_then(|_| {
+                mfd_command_values(text)
+                    .into_iter()
+                    .try_for_each(|value| self.vendor_command(command, value))
+            });
+
+        self.log_failure(result, "set MFD string");
+    }
+
+    /// Sets the primary clock on the throttle to the given time in 24-hour
+    /// format.
+    fn set_clock(&self, hour: u32, minute: u32) {
+        debug!("Setting clock to {:02}:{:02}", hour, minute);
+
+        let result = self.vendor_command(X52PRO_CLOCK_COMMAND, clock_command_value(hour, minute));
+        self.log_failure(result, "set clock");
+    }
+}
+
+/// Returns the trimmed value of the named attribute of the given sysfs USB
+/// device, if present.
+fn sysfs_attribute(device_path: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(device_path.join(name))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+/// Returns the path of the usbfs device file for the given bus and device.
+fn device_file_path(bus_number: u32, device_number: u32) -> PathBuf {
+    PathBuf::from(USB_DEVICE_FILES_PATH)
+        .join(format!("{:03}", bus_number))
+        .join(format!("{:03}", device_number))
+}
+
+/// Returns the value for an LED command. The device numbers its LEDs from 1
+/// in the same order as the DirectOutput ids, which number from 0.
+fn led_command_value(id: u32, active: bool) -> u16 {
+    (((id + 1) as u16) << 8) | active as u16
+}
+
+/// Returns the value for the clock command, which holds the hour in the high
+/// byte and the minute in the low byte, with the top bit selecting 24-hour
+/// format.
+fn clock_command_value(hour: u32, minute: u32) -> u16 {
+    X52PRO_CLOCK_24_HOUR | (hour as u16) << 8 | minute as u16
+}
+
+/// Returns the values for the MFD commands that write the given text, each
+/// holding two characters with the first in the low byte. Characters outside
+/// ASCII are shown as `?` and odd length text is padded with a space.
+fn mfd_command_values(text: &str) -> Vec<u16> {
+    let bytes: Vec<u16> = text
+        .chars()
+        .map(|character| {
+            if character.is_ascii() {
+                character as u16
+            } else {
+                b'?' as u16
+            }
+        })
+        .collect();
+
+    bytes
+        .chunks(2)
+        .map(|pair| pair[0] | pair.get(1).copied().unwrap_or(b' ' as u16) << 8)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn led_command_value_offsets_id_and_sets_state() {
+        assert_eq!(led_command_value(0, true), 0x0101);
+        assert_eq!(led_command_value(0, false), 0x0100);
+        assert_eq!(led_command_value(19, true), 0x1401);
+    }
+
+    #[test]
+    fn clock_command_value_sets_24_hour_time() {
+        assert_eq!(clock_command_value(0, 0), 0x8000);
+        assert_eq!(clock_command_value(23, 59), 0x973b);
+    }
+
+    #[test]
+    fn mfd_command_values_pairs_characters_and_pads() {
+        assert_eq!(mfd_command_values("AB"), vec![0x4241]);
+        assert_eq!(mfd_command_values("ABC"), vec![0x4241, 0x2043]);
+        assert_eq!(mfd_command_values("é"), vec![0x203f]);
+        assert_eq!(mfd_command_values(""), Vec::<u16>::new());
+    }
+
+    #[test]
+    fn device_file_path_pads_bus_and_device_numbers() {
+        assert_eq!(
+            device_file_path(1, 12),
+            PathBuf::from("/dev/bus/usb/001/012")
+        );
+    }
+}